@@ -0,0 +1,264 @@
+//! History export module for the Ryz Labs Wallet Balance Tracker
+//! Provides spreadsheet-safe serialization of wallet transaction history
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::money::Money;
+use crate::{Transaction, transaction_history_with_balances};
+use log::info;
+use serde::Serialize;
+
+/// File formats supported for history export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// JSON array of transactions with running balances, amounts in minor units
+    Json,
+    /// CSV rows as produced by `export_csv`
+    Csv,
+}
+
+impl ExportFormat {
+    /// File extension used for exported files
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Output formats supported by `write_transaction_history`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// Human-readable lines as shown by `print_transaction_history`
+    Plain,
+    /// JSON array as produced by `export_json`, with amounts in minor units
+    Json,
+    /// CSV rows as produced by `export_csv`
+    Csv,
+}
+
+/// A transaction paired with the wallet's balance after it, as exported to JSON
+#[derive(Serialize)]
+struct HistoryEntry<'a> {
+    #[serde(flatten)]
+    transaction: &'a Transaction,
+    running_balance: i64,
+}
+
+/// Characters that make spreadsheet applications interpret a cell as a formula
+const FORMULA_TRIGGERS: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Escapes a text value for safe inclusion in a CSV file
+///
+/// Values starting with a formula trigger are prefixed with a single quote so
+/// spreadsheet applications treat them as plain text, and every value is quoted
+/// so embedded commas, quotes, and newlines cannot break the row structure.
+///
+/// # Arguments
+/// * `value` - Raw text to escape
+///
+/// # Returns
+/// * `String` - Quoted, injection-safe CSV field
+pub fn sanitize_csv_field(value: &str) -> String {
+    let neutralized = if value.starts_with(FORMULA_TRIGGERS) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    format!("\"{}\"", neutralized.replace('"', "\"\""))
+}
+
+/// Writes a wallet's transaction history as CSV
+///
+/// Produces a `type,wallet,amount,running_balance` header row followed by one row
+/// per transaction involving the wallet, with the running balance computed the
+/// same way as `print_transaction_history`. Amounts are written in major units,
+/// e.g. `10.50`.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to export
+/// * `wallet_address` - Address of the wallet to export history for
+/// * `writer` - Destination for the CSV output
+///
+/// # Returns
+/// * `io::Result<()>` - Success or failure of the write
+pub fn export_csv(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    writer: impl Write,
+) -> io::Result<()> {
+    write_csv(transactions, wallet_address, false, writer)
+}
+
+/// Writes a wallet's transaction history as CSV with an extra `memo` column
+///
+/// Rows match `export_csv`, followed by the transaction's memo (empty if none),
+/// neutralized against formula injection like every other text field.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to export
+/// * `wallet_address` - Address of the wallet to export history for
+/// * `writer` - Destination for the CSV output
+///
+/// # Returns
+/// * `io::Result<()>` - Success or failure of the write
+pub fn export_csv_with_memos(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    writer: impl Write,
+) -> io::Result<()> {
+    write_csv(transactions, wallet_address, true, writer)
+}
+
+/// Writes the CSV rows shared by `export_csv` and `export_csv_with_memos`
+fn write_csv(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    include_memo: bool,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let memo_header = if include_memo { ",memo" } else { "" };
+    writeln!(writer, "type,wallet,amount,running_balance{}", memo_header)?;
+
+    let mut rows = 0;
+    for (tx, balance) in transaction_history_with_balances(transactions, wallet_address) {
+        write!(
+            writer,
+            "{},{},{},{}",
+            sanitize_csv_field(&tx.transaction_type.to_string()),
+            sanitize_csv_field(&tx.wallet_address),
+            tx.amount,
+            Money::from_minor(balance),
+        )?;
+        if include_memo {
+            write!(writer, ",{}", sanitize_csv_field(tx.memo.as_deref().unwrap_or("")))?;
+        }
+        writeln!(writer)?;
+        rows += 1;
+    }
+
+    info!("Exported {} CSV rows for wallet {}", rows, wallet_address);
+    Ok(())
+}
+
+/// Writes a wallet's transaction history as a JSON array
+///
+/// Each element holds the transaction's fields plus a `running_balance`
+/// from `transaction_history_with_balances`. Unlike the plain and CSV formats,
+/// `amount`, `fee` and `running_balance` are integers in minor units (`100` for
+/// `1.00`), matching how transactions are serialized in the state file.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to export
+/// * `wallet_address` - Address of the wallet to export history for
+/// * `writer` - Destination for the JSON output
+///
+/// # Returns
+/// * `io::Result<()>` - Success or failure of the write
+pub fn export_json(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let entries: Vec<HistoryEntry> = transaction_history_with_balances(transactions, wallet_address)
+        .into_iter()
+        .map(|(transaction, running_balance)| HistoryEntry {
+            transaction,
+            running_balance,
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut writer, &entries)?;
+    writeln!(writer)?;
+    info!("Exported {} JSON entries for wallet {}", entries.len(), wallet_address);
+    Ok(())
+}
+
+/// Writes a wallet's transaction history in the chosen format
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to write
+/// * `wallet_address` - Address of the wallet to write history for
+/// * `format` - Output format
+/// * `writer` - Destination for the output
+///
+/// # Returns
+/// * `io::Result<()>` - Success or failure of the write
+pub fn write_transaction_history(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    format: HistoryFormat,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    match format {
+        HistoryFormat::Plain => {
+            writeln!(writer, "Transaction history for wallet {}:", wallet_address)?;
+            for (tx, balance) in transaction_history_with_balances(transactions, wallet_address) {
+                writeln!(writer, "{} | Running balance: {}", tx, Money::from_minor(balance))?;
+            }
+            Ok(())
+        }
+        HistoryFormat::Json => export_json(transactions, wallet_address, writer),
+        HistoryFormat::Csv => export_csv(transactions, wallet_address, writer),
+    }
+}
+
+/// Turns a wallet address into a safe file name stem
+fn sanitize_file_stem(address: &str) -> String {
+    let stem: String = address
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if stem.is_empty() {
+        String::from("wallet")
+    } else {
+        stem
+    }
+}
+
+/// Exports every wallet's history to its own file in a directory
+///
+/// Files are named after the sanitized wallet address; when two addresses
+/// sanitize to the same name, a numeric suffix keeps the files apart.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to export
+/// * `dir` - Directory to write the files into (created if missing)
+/// * `format` - File format to write
+///
+/// # Returns
+/// * `io::Result<Vec<PathBuf>>` - Paths of the written files, in wallet address order
+pub fn export_all(
+    transactions: &[Transaction],
+    dir: &Path,
+    format: ExportFormat,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let mut used_stems = HashSet::new();
+    let mut written = Vec::new();
+    for address in crate::wallet_addresses(transactions) {
+        let base = sanitize_file_stem(address);
+        let mut stem = base.clone();
+        let mut suffix = 2;
+        while !used_stems.insert(stem.clone()) {
+            stem = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+
+        let path = dir.join(format!("{}.{}", stem, format.extension()));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        match format {
+            ExportFormat::Json => export_json(transactions, address, &mut writer)?,
+            ExportFormat::Csv => export_csv(transactions, address, &mut writer)?,
+        }
+        writer.flush()?;
+        written.push(path);
+    }
+
+    info!("Exported {} wallet histories to {}", written.len(), dir.display());
+    Ok(written)
+}
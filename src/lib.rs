@@ -4,21 +4,41 @@
 extern crate log;
 
 use fern::Dispatch;
-use chrono::Local;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
 use log::{info, error, LevelFilter};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use money::Money;
 
 // Export terminal module for external use
 pub mod terminal;
+// Export history export helpers for external use
+pub mod export;
+// Export amount parsing helpers for external use
+pub mod money;
+// Export transaction storage backends for external use
+pub mod store;
+// Export thread-safe wallet state for external use
+pub mod shared;
 
 /// Represents the types of transactions supported by the wallet system
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionType {
     /// Represents funds being added to a wallet
     Deposit,
     /// Represents funds being removed from a wallet
     Withdrawal,
+    /// Represents funds moving from the transaction's wallet to another wallet
+    Transfer {
+        /// Address of the wallet receiving the funds
+        to: String,
+    },
 }
 
 // Implement display formatting for transaction types
@@ -27,29 +47,165 @@ impl fmt::Display for TransactionType {
         match self {
             TransactionType::Deposit => write!(f, "Deposit"),
             TransactionType::Withdrawal => write!(f, "Withdrawal"),
+            TransactionType::Transfer { .. } => write!(f, "Transfer"),
         }
     }
 }
 
+/// Settlement state of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    /// The transaction has settled
+    #[default]
+    Confirmed,
+    /// The transaction has been submitted but not yet settled
+    Pending,
+}
+
+/// Format used when displaying transaction timestamps
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Currency assigned to transactions that do not specify one
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+// Source of unique identifiers for transactions created in this process
+static NEXT_TRANSACTION_ID: AtomicU64 = AtomicU64::new(1);
+
 /// Represents a single transaction in the wallet system
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
-    /// Type of transaction (Deposit/Withdrawal)
+    /// Identifier used to refer to the transaction in corrections and reports
+    pub id: u64,
+    /// Type of transaction (Deposit/Withdrawal/Transfer)
     pub transaction_type: TransactionType,
-    /// Address of the wallet involved in the transaction
+    /// Address of the wallet involved in the transaction (the source for transfers)
     pub wallet_address: String,
     /// Amount of funds involved in the transaction
-    pub amount: i64,
+    pub amount: Money,
+    /// Currency code the amount is denominated in
+    pub currency: String,
+    /// Time at which the transaction was recorded
+    pub timestamp: DateTime<Local>,
+    /// Fee in minor units charged to the transaction's wallet on top of the amount
+    pub fee: i64,
+    /// Optional free-form note or tag attached to the transaction
+    pub memo: Option<String>,
+    /// Settlement state; pending transactions still count toward calculated balances
+    pub status: TransactionStatus,
+    /// Optional client-supplied key identifying a request, so a replayed request is
+    /// recognized and not applied twice
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+impl Transaction {
+    /// Creates a confirmed, fee-free, memo-less, unkeyed transaction in the default currency,
+    /// stamped with the current local time
+    ///
+    /// Each call assigns the next identifier from a process-wide sequence.
+    ///
+    /// # Arguments
+    /// * `transaction_type` - Type of transaction (Deposit/Withdrawal/Transfer)
+    /// * `wallet_address` - Address of the wallet involved in the transaction
+    /// * `amount` - Amount of funds involved in the transaction (plain integers are minor units)
+    pub fn new(
+        transaction_type: TransactionType,
+        wallet_address: impl Into<String>,
+        amount: impl Into<Money>,
+    ) -> Self {
+        Transaction {
+            id: NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed),
+            transaction_type,
+            wallet_address: wallet_address.into(),
+            amount: amount.into(),
+            currency: DEFAULT_CURRENCY.to_string(),
+            timestamp: Local::now(),
+            fee: 0,
+            memo: None,
+            status: TransactionStatus::Confirmed,
+            idempotency_key: None,
+        }
+    }
+
+    /// Checks whether the transaction affects a wallet's balance
+    ///
+    /// Transfers involve both the source wallet and the destination wallet.
+    pub fn involves(&self, wallet_address: &str) -> bool {
+        self.wallet_address == wallet_address
+            || matches!(&self.transaction_type, TransactionType::Transfer { to } if to == wallet_address)
+    }
+
+    /// Builds the transaction that undoes this one's balance effects
+    ///
+    /// The reversal carries no fee or idempotency key of its own; the original fee is
+    /// not refunded.
+    fn reversed(&self) -> Transaction {
+        let (transaction_type, wallet_address) = match &self.transaction_type {
+            TransactionType::Deposit => (TransactionType::Withdrawal, self.wallet_address.clone()),
+            TransactionType::Withdrawal => (TransactionType::Deposit, self.wallet_address.clone()),
+            TransactionType::Transfer { to } => (
+                TransactionType::Transfer {
+                    to: self.wallet_address.clone(),
+                },
+                to.clone(),
+            ),
+        };
+        Transaction {
+            transaction_type,
+            wallet_address,
+            fee: 0,
+            idempotency_key: None,
+            ..self.clone()
+        }
+    }
+
+    /// Signed effect of the transaction on a wallet's balance in minor units, including
+    /// the fee paid by the transaction's wallet, without validation
+    ///
+    /// The effect saturates at the i64 bounds, so unvalidated amounts and fees cannot overflow.
+    pub(crate) fn balance_delta(&self, wallet_address: &str) -> i64 {
+        let amount = self.amount.minor_units();
+        let mut delta = if self.wallet_address == wallet_address { 0i64.saturating_sub(self.fee) } else { 0 };
+        match &self.transaction_type {
+            TransactionType::Deposit if self.wallet_address == wallet_address => delta = delta.saturating_add(amount),
+            TransactionType::Withdrawal if self.wallet_address == wallet_address => delta = delta.saturating_sub(amount),
+            TransactionType::Transfer { to } => {
+                if self.wallet_address == wallet_address {
+                    delta = delta.saturating_sub(amount);
+                }
+                if to == wallet_address {
+                    delta = delta.saturating_add(amount);
+                }
+            }
+            _ => {}
+        }
+        delta
+    }
+
+    /// Ensures newly created transactions receive identifiers above `max_id`
+    ///
+    /// Used after loading a saved history so new transactions cannot reuse its identifiers.
+    pub(crate) fn reserve_ids_through(max_id: u64) {
+        NEXT_TRANSACTION_ID.fetch_max(max_id.saturating_add(1), Ordering::Relaxed);
+    }
 }
 
 // Implement display formatting for transactions
 impl fmt::Display for Transaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{} of {} to {}",
-            self.transaction_type, self.amount, self.wallet_address
-        )
+        write!(f, "{} | ", self.timestamp.format(TIMESTAMP_FORMAT))?;
+        match &self.transaction_type {
+            TransactionType::Transfer { to } => write!(
+                f,
+                "{} of {} from {} to {}",
+                self.transaction_type, self.amount, self.wallet_address, to
+            ),
+            _ => write!(
+                f,
+                "{} of {} to {}",
+                self.transaction_type, self.amount, self.wallet_address
+            ),
+        }
     }
 }
 
@@ -65,19 +221,241 @@ pub enum WalletError {
         requested: i64,
         available: i64,
     },
+    /// Error for withdrawals pushing a day's total past the daily limit
+    #[error("Daily withdrawal limit of {limit} exceeded on {date}")]
+    DailyLimitExceeded {
+        date: NaiveDate,
+        limit: i64,
+    },
+    /// Error for a single transaction larger than the permitted maximum
+    #[error("Transaction amount {amount} exceeds the limit of {limit}")]
+    AmountExceedsLimit {
+        amount: i64,
+        limit: i64,
+    },
+    /// Error for operations on a wallet that has been frozen
+    #[error("Wallet {0} is frozen")]
+    WalletFrozen(String),
+    /// Error for references to a transaction identifier that does not exist
+    #[error("Transaction {0} not found")]
+    TransactionNotFound(u64),
+    /// Error for a currency conversion without a supplied exchange rate
+    #[error("Missing exchange rate for currency {0}")]
+    MissingExchangeRate(String),
+    /// Error for amount text that cannot be read under the configured locale
+    #[error("Unparseable amount: {0}")]
+    UnparseableAmount(String),
+    /// Error for a running balance that no longer fits in an i64
+    #[error("Balance overflow in wallet {wallet}")]
+    BalanceOverflow {
+        wallet: String,
+    },
+    /// Error for a sliding window that cannot hold any transactions
+    #[error("Invalid window size: {0}")]
+    InvalidWindow(usize),
+    /// Error for wallet addresses rejected by the address rules
+    #[error("Invalid wallet address: {0}")]
+    InvalidAddress(String),
+    /// Error for forecast scenarios whose probabilities are not a valid distribution
+    #[error("Scenario probabilities sum to {0}, expected 1.0")]
+    InvalidProbabilities(f64),
+    /// Error identifying which wallet's history failed during a multi-wallet calculation
+    #[error("Wallet {wallet} failed validation: {source}")]
+    WalletValidation {
+        wallet: String,
+        #[source]
+        source: Box<WalletError>,
+    },
+    /// Error for a transaction store that could not be read or written
+    #[error("Transaction store error: {0}")]
+    Storage(#[from] std::io::Error),
+    /// Error for a total balance that moved by more than net deposits and withdrawals
+    #[error("Conservation violated: expected total balance {expected}, found {actual}")]
+    ConservationViolation {
+        expected: i64,
+        actual: i64,
+    },
+    /// Error for a transaction whose idempotency key was already recorded
+    #[error("Duplicate transaction: key '{0}' was already recorded")]
+    DuplicateTransaction(String),
+    /// Error for a wallet index pointing at a log position that no longer holds the wallet's transaction
+    #[error("Stale index for wallet {wallet}: position {position} is not one of its transactions")]
+    StaleIndex {
+        wallet: String,
+        position: usize,
+    },
+}
+
+impl WalletError {
+    /// Stable name of the error variant, used to group errors in reports
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WalletError::InvalidAmount(_) => "InvalidAmount",
+            WalletError::InsufficientFunds { .. } => "InsufficientFunds",
+            WalletError::DailyLimitExceeded { .. } => "DailyLimitExceeded",
+            WalletError::AmountExceedsLimit { .. } => "AmountExceedsLimit",
+            WalletError::WalletFrozen(_) => "WalletFrozen",
+            WalletError::TransactionNotFound(_) => "TransactionNotFound",
+            WalletError::MissingExchangeRate(_) => "MissingExchangeRate",
+            WalletError::UnparseableAmount(_) => "UnparseableAmount",
+            WalletError::BalanceOverflow { .. } => "BalanceOverflow",
+            WalletError::InvalidWindow(_) => "InvalidWindow",
+            WalletError::InvalidProbabilities(_) => "InvalidProbabilities",
+            WalletError::InvalidAddress(_) => "InvalidAddress",
+            WalletError::WalletValidation { .. } => "WalletValidation",
+            WalletError::Storage(_) => "Storage",
+            WalletError::ConservationViolation { .. } => "ConservationViolation",
+            WalletError::DuplicateTransaction(_) => "DuplicateTransaction",
+            WalletError::StaleIndex { .. } => "StaleIndex",
+        }
+    }
+}
+
+/// Overview of the errors encountered while processing a batch of transactions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorSummary {
+    /// Indices of the failing records, grouped by error kind
+    pub by_kind: BTreeMap<String, Vec<usize>>,
+}
+
+impl ErrorSummary {
+    /// Number of errors of the given kind
+    pub fn count(&self, kind: &str) -> usize {
+        self.by_kind.get(kind).map_or(0, Vec::len)
+    }
+
+    /// Total number of errors across all kinds
+    pub fn total(&self) -> usize {
+        self.by_kind.values().map(Vec::len).sum()
+    }
+}
+
+/// Rules a wallet address must satisfy to be accepted
+///
+/// Letters and digits are always allowed; stricter or looser policies can be
+/// built by adjusting the length bounds and the extra permitted characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressRules {
+    /// Shortest accepted address, in characters
+    pub min_length: usize,
+    /// Longest accepted address, in characters
+    pub max_length: usize,
+    /// Characters permitted in addition to ASCII letters and digits
+    pub extra_chars: Vec<char>,
+}
+
+impl Default for AddressRules {
+    fn default() -> Self {
+        AddressRules {
+            min_length: 1,
+            max_length: 64,
+            extra_chars: vec!['_'],
+        }
+    }
+}
+
+impl AddressRules {
+    /// Checks an address against the rules
+    ///
+    /// # Arguments
+    /// * `addr` - Wallet address to check
+    ///
+    /// # Returns
+    /// * `Result<(), WalletError>` - Success, or `InvalidAddress` naming the rejected address
+    pub fn validate(&self, addr: &str) -> Result<(), WalletError> {
+        let length = addr.chars().count();
+        let allowed = |c: char| c.is_ascii_alphanumeric() || self.extra_chars.contains(&c);
+
+        if length < self.min_length || length > self.max_length || !addr.chars().all(allowed) {
+            error!("Rejected wallet address '{}' under {:?}", addr, self);
+            return Err(WalletError::InvalidAddress(addr.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Checks a wallet address against the default address rules
+///
+/// Addresses must be 1 to 64 characters of ASCII letters, digits, or underscores.
+///
+/// # Arguments
+/// * `addr` - Wallet address to check
+///
+/// # Returns
+/// * `Result<(), WalletError>` - Success, or `InvalidAddress` naming the rejected address
+pub fn validate_wallet_address(addr: &str) -> Result<(), WalletError> {
+    AddressRules::default().validate(addr)
+}
+
+/// Rules a proposed withdrawal can be checked against
+#[derive(Debug, Clone)]
+pub enum WithdrawalPolicy {
+    /// The wallet must hold at least the withdrawal amount plus its fee
+    SufficientFunds,
+    /// A single withdrawal may not exceed the given amount
+    MaxSingle(i64),
+    /// Withdrawals, outgoing transfers and fees on the `as_of` date, including this
+    /// withdrawal and its fee, may not exceed the given total
+    DailyLimit(i64),
+    /// The wallet must not be one of the given frozen addresses
+    NotFrozen(HashSet<String>),
+}
+
+/// Environment variable overriding the directory log files are written to
+pub const LOG_DIR_ENV: &str = "WALLET_LOG_DIR";
+
+/// Environment variable selecting the terminal's log level, e.g. "debug" or "warn"
+pub const LOG_LEVEL_ENV: &str = "WALLET_LOG_LEVEL";
+
+/// Reads a log level setting such as the value of `$WALLET_LOG_LEVEL`
+///
+/// # Arguments
+/// * `value` - Level name like "error", "warn", "info", or "debug" (case-insensitive), if set
+///
+/// # Returns
+/// * `LevelFilter` - The named level, or `Info` when the value is missing or not a level
+pub fn parse_log_level(value: Option<&str>) -> LevelFilter {
+    value
+        .and_then(|level| LevelFilter::from_str(level.trim()).ok())
+        .unwrap_or(LevelFilter::Info)
+}
+
+/// Resolves the directory a component writes its log files to
+///
+/// Logs go under `$WALLET_LOG_DIR` when it is set, and under a `ryz_labs_logs`
+/// folder in the OS temp directory otherwise.
+///
+/// # Arguments
+/// * `component` - Subdirectory for the component, e.g. "tests" or "src"
+///
+/// # Returns
+/// * `PathBuf` - Directory for the component's log files (not created)
+pub fn log_directory(component: &str) -> PathBuf {
+    let base = std::env::var_os(LOG_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("ryz_labs_logs"));
+    base.join(component)
 }
 
 /// Initializes the logging system with test-specific configuration
-/// 
+///
+/// The log file is created in `log_directory("tests")`, creating the
+/// directory first if needed. Only one logger can be installed per process,
+/// so calling this again returns `InitError::SetLoggerError` rather than panicking.
+///
 /// # Arguments
 /// * `test_name` - Identifier for the test being executed
-pub fn init_logging(test_name: &str) {
-    let test_name = test_name.to_string();
-    let log_file_path = format!(
-        "/home/jtdev/Desktop/web_3/ryz-labs/logs/tests/{}_log_output.log",
-        test_name
-    );
+///
+/// # Returns
+/// * `Result<(), fern::InitError>` - Success, `InitError::Io` if the log file cannot be
+///   created, or `InitError::SetLoggerError` if logging was already initialized
+pub fn init_logging(test_name: &str) -> Result<(), fern::InitError> {
+    let log_dir = log_directory("tests");
+    std::fs::create_dir_all(&log_dir)?;
+    let log_file_path = log_dir.join(format!("{}_log_output.log", test_name));
 
+    let test_name = test_name.to_string();
     Dispatch::new()
         .format(move |out, message, record| {
             out.finish(format_args!(
@@ -89,9 +467,9 @@ pub fn init_logging(test_name: &str) {
             ))
         })
         .level(LevelFilter::Info)
-        .chain(fern::log_file(log_file_path).unwrap())
-        .apply()
-        .unwrap();
+        .chain(fern::log_file(log_file_path)?)
+        .apply()?;
+    Ok(())
 }
 
 /// Logs a section header for test organization
@@ -102,6 +480,128 @@ pub fn log_section_header(section: &str) {
     info!("========== {} ==========", section);
 }
 
+/// Tracks a wallet's running balance while validating each transaction
+///
+/// Shared by every function that replays a wallet's history so validation
+/// rules stay identical across balance calculations and analytics.
+struct BalanceTracker<'a> {
+    wallet_address: &'a str,
+    balance: Money,
+    /// How far below zero a charge may take the balance
+    overdraft_limit: Money,
+}
+
+impl<'a> BalanceTracker<'a> {
+    /// Creates a tracker starting from a zero balance
+    fn new(wallet_address: &'a str) -> Self {
+        Self::starting_at(wallet_address, Money::ZERO)
+    }
+
+    /// Creates a tracker starting from a known opening balance
+    fn starting_at(wallet_address: &'a str, balance: Money) -> Self {
+        BalanceTracker {
+            wallet_address,
+            balance,
+            overdraft_limit: Money::ZERO,
+        }
+    }
+
+    /// Allows charges to take the balance down to `-overdraft_limit`
+    fn with_overdraft(self, overdraft_limit: Money) -> Self {
+        BalanceTracker { overdraft_limit, ..self }
+    }
+
+    /// Validates and applies a transaction, returning the updated balance in minor units
+    ///
+    /// The transaction's wallet pays its fee on top of the transaction's own effect.
+    fn apply(&mut self, tx: &Transaction) -> Result<i64, WalletError> {
+        use TransactionType::*;
+
+        // Validate transaction amount and fee
+        if tx.amount.is_negative() {
+            error!(
+                "Invalid transaction amount: {} in transaction {:?}",
+                tx.amount, tx
+            );
+            return Err(WalletError::InvalidAmount(tx.amount.minor_units()));
+        }
+        if tx.fee < 0 {
+            error!("Invalid transaction fee: {} in transaction {:?}", tx.fee, tx);
+            return Err(WalletError::InvalidAmount(tx.fee));
+        }
+        let fee = Money::from_minor(tx.fee);
+
+        // Update balance based on transaction type
+        match &tx.transaction_type {
+            Deposit => {
+                info!("Deposit of {} to {}", tx.amount, tx.wallet_address);
+                self.credit(tx.amount)?;
+                self.debit(tx, fee)?;
+            }
+            Withdrawal => {
+                self.debit(tx, tx.amount.checked_add(fee).ok_or_else(|| self.overflow())?)?;
+                info!("Withdrawal of {} from {}", tx.amount, tx.wallet_address);
+            }
+            Transfer { to } => {
+                // Source leg: funds and the fee leave the sending wallet
+                if tx.wallet_address == self.wallet_address {
+                    self.debit(tx, tx.amount.checked_add(fee).ok_or_else(|| self.overflow())?)?;
+                    info!("Transfer of {} out of {} to {}", tx.amount, tx.wallet_address, to);
+                }
+                // Destination leg: funds arrive in the receiving wallet
+                if to == self.wallet_address {
+                    info!("Transfer of {} into {} from {}", tx.amount, to, tx.wallet_address);
+                    self.credit(tx.amount)?;
+                }
+            }
+        }
+        if tx.fee > 0 && tx.wallet_address == self.wallet_address {
+            info!("Fee of {} charged to {}", fee, tx.wallet_address);
+        }
+
+        Ok(self.balance.minor_units())
+    }
+
+    /// Returns the tracked balance in minor units
+    fn minor_units(&self) -> i64 {
+        self.balance.minor_units()
+    }
+
+    /// Removes what a transaction charges the wallet after verifying sufficient funds
+    ///
+    /// Funds include the overdraft allowance, so the reported available amount is
+    /// the balance plus the overdraft limit.
+    fn debit(&mut self, tx: &Transaction, charge: Money) -> Result<(), WalletError> {
+        let available = self.balance.minor_units() as i128 + self.overdraft_limit.minor_units() as i128;
+        if (charge.minor_units() as i128) > available {
+            error!(
+                "Insufficient funds for {} of {} (fee {}) from {}. Available balance: {} (overdraft limit {})",
+                tx.transaction_type, tx.amount, tx.fee, self.wallet_address, self.balance, self.overdraft_limit
+            );
+            return Err(WalletError::InsufficientFunds {
+                requested: charge.minor_units(),
+                available: i64::try_from(available).unwrap_or(i64::MAX),
+            });
+        }
+        self.balance = self.balance.checked_sub(charge).ok_or_else(|| self.overflow())?;
+        Ok(())
+    }
+
+    /// Adds an amount to the balance, refusing to wrap past `i64::MAX` minor units
+    fn credit(&mut self, amount: Money) -> Result<(), WalletError> {
+        self.balance = self.balance.checked_add(amount).ok_or_else(|| self.overflow())?;
+        Ok(())
+    }
+
+    /// Builds the overflow error for the tracked wallet
+    fn overflow(&self) -> WalletError {
+        error!("Balance overflow in wallet {} at balance {}", self.wallet_address, self.balance);
+        WalletError::BalanceOverflow {
+            wallet: self.wallet_address.to_string(),
+        }
+    }
+}
+
 /// Calculates the current balance for a specific wallet
 /// 
 /// # Arguments
@@ -109,69 +609,2656 @@ pub fn log_section_header(section: &str) {
 /// * `wallet_address` - Address of the wallet to calculate balance for
 /// 
 /// # Returns
-/// * `Result<i64, WalletError>` - Calculated balance or error if validation fails
+/// * `Result<i64, WalletError>` - Calculated balance in minor units or error if validation fails
 pub fn calculate_wallet_balance(
     transactions: &[Transaction],
     wallet_address: &str,
 ) -> Result<i64, WalletError> {
-    use TransactionType::*;
+    calculate_wallet_balance_with_overdraft(transactions, wallet_address, 0)
+}
 
-    let mut balance = 0;
+/// Calculates the current balance for a wallet allowed to overdraw
+///
+/// Withdrawals, outgoing transfers, and fees are accepted as long as the
+/// resulting balance stays at or above `-overdraft_limit`.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet_address` - Address of the wallet to calculate balance for
+/// * `overdraft_limit` - How far below zero the balance may go, in minor units
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Calculated balance in minor units, possibly negative,
+///   `InvalidAmount` for a negative limit, or error if validation fails
+pub fn calculate_wallet_balance_with_overdraft(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    overdraft_limit: i64,
+) -> Result<i64, WalletError> {
+    if overdraft_limit < 0 {
+        error!("Invalid overdraft limit {} for wallet {}", overdraft_limit, wallet_address);
+        return Err(WalletError::InvalidAmount(overdraft_limit));
+    }
+    let mut tracker =
+        BalanceTracker::new(wallet_address).with_overdraft(Money::from_minor(overdraft_limit));
 
     // Process each transaction for the specified wallet
-    for tx in transactions.iter().filter(|tx| tx.wallet_address == wallet_address) {
-        // Validate transaction amount
-        if tx.amount < 0 {
-            error!(
-                "Invalid transaction amount: {} in transaction {:?}",
-                tx.amount, tx
-            );
-            return Err(WalletError::InvalidAmount(tx.amount));
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet_address)) {
+        tracker.apply(tx)?;
+    }
+
+    info!("Final balance for wallet {}: {}", wallet_address, tracker.minor_units());
+    Ok(tracker.minor_units())
+}
+
+/// Calculates a wallet's balance from any source of transactions
+///
+/// Generalizes `calculate_wallet_balance` so callers streaming transactions from a
+/// custom iterator do not need to collect them into a slice first.
+///
+/// # Arguments
+/// * `iter` - Transactions to process, in order
+/// * `wallet` - Address of the wallet to calculate balance for
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Calculated balance or error if validation fails
+pub fn balance_from_iter<I: IntoIterator<Item = Transaction>>(
+    iter: I,
+    wallet: &str,
+) -> Result<i64, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet);
+
+    for tx in iter.into_iter().filter(|tx| tx.involves(wallet)) {
+        tracker.apply(&tx)?;
+    }
+
+    info!("Final balance for wallet {}: {}", wallet, tracker.minor_units());
+    Ok(tracker.minor_units())
+}
+
+/// Collects every wallet address appearing in the transactions, including transfer destinations
+pub(crate) fn wallet_addresses(transactions: &[Transaction]) -> BTreeSet<&str> {
+    let mut addresses = BTreeSet::new();
+    for tx in transactions {
+        addresses.insert(tx.wallet_address.as_str());
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            addresses.insert(to.as_str());
         }
+    }
+    addresses
+}
 
-        // Update balance based on transaction type
-        match tx.transaction_type {
-            Deposit => {
-                info!("Deposit of {} to {}", tx.amount, tx.wallet_address);
-                balance += tx.amount;
-            }
-            Withdrawal => {
-                // Verify sufficient funds for withdrawal
-                if balance < tx.amount {
-                    error!(
-                        "Insufficient funds for withdrawal of {} from {}. Available balance: {}",
-                        tx.amount, tx.wallet_address, balance
-                    );
-                    return Err(WalletError::InsufficientFunds {
-                        requested: tx.amount,
-                        available: balance,
-                    });
+/// Replays a wallet's history, returning the index and running balance after each of its transactions
+fn running_balances(
+    transactions: &[Transaction],
+    wallet_address: &str,
+) -> Result<Vec<(usize, i64)>, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet_address);
+    let mut balances = Vec::new();
+
+    for (index, tx) in transactions.iter().enumerate() {
+        if tx.involves(wallet_address) {
+            balances.push((index, tracker.apply(tx)?));
+        }
+    }
+
+    Ok(balances)
+}
+
+/// Replays a wallet's history with withdrawals consuming the oldest funds first
+///
+/// Returns each incoming transaction (deposit or received transfer) that still has
+/// funds left, paired with the unconsumed amount, oldest first.
+fn fifo_lots<'a>(
+    transactions: &'a [Transaction],
+    wallet_address: &str,
+) -> Result<Vec<(&'a Transaction, i64)>, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet_address);
+    let mut lots: VecDeque<(&Transaction, i64)> = VecDeque::new();
+
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet_address)) {
+        let before = tracker.minor_units();
+        let after = tracker.apply(tx)?;
+
+        if after > before {
+            lots.push_back((tx, after - before));
+        } else {
+            // Consume outgoing funds from the oldest lots first
+            let mut outgoing = before - after;
+            while outgoing > 0 {
+                let Some(oldest) = lots.front_mut() else { break };
+                let taken = oldest.1.min(outgoing);
+                oldest.1 -= taken;
+                outgoing -= taken;
+                if oldest.1 == 0 {
+                    lots.pop_front();
                 }
-                info!("Withdrawal of {} from {}", tx.amount, tx.wallet_address);
-                balance -= tx.amount;
             }
         }
     }
 
-    info!("Final balance for wallet {}: {}", wallet_address, balance);
-    Ok(balance)
+    Ok(lots.into_iter().collect())
 }
 
-/// Displays transaction history for a specific wallet
-/// 
+/// Finds the transaction at which a wallet's balance first reaches a threshold
+///
 /// # Arguments
-/// * `transactions` - Slice of transactions to display
-/// * `wallet_address` - Address of the wallet to show history for
-pub fn print_transaction_history(transactions: &[Transaction], wallet_address: &str) {
-    let mut balance = 0;
-    println!("Transaction history for wallet {}:", wallet_address);
-    
-    // Display each transaction with running balance
-    for tx in transactions.iter().filter(|tx| tx.wallet_address == wallet_address) {
-        match tx.transaction_type {
-            TransactionType::Deposit => balance += tx.amount,
-            TransactionType::Withdrawal => balance -= tx.amount,
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to inspect
+/// * `threshold` - Balance level to watch for
+///
+/// # Returns
+/// * `Result<Option<usize>, WalletError>` - Index of the first transaction after which
+///   the running balance is at or above `threshold`, or None if it never gets there
+pub fn transaction_crossing_threshold(
+    transactions: &[Transaction],
+    wallet: &str,
+    threshold: i64,
+) -> Result<Option<usize>, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet);
+
+    for (index, tx) in transactions.iter().enumerate() {
+        if !tx.involves(wallet) {
+            continue;
+        }
+        if tracker.apply(tx)? >= threshold {
+            info!("Wallet {} reached {} at transaction {}", wallet, threshold, index);
+            return Ok(Some(index));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Calculates a wallet's average balance weighted by how long each balance was held
+///
+/// Each running balance is weighted by the time until the wallet's next transaction,
+/// so the final balance (which has no end point) does not contribute.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process, in chronological order
+/// * `wallet` - Address of the wallet to average
+///
+/// # Returns
+/// * `Result<Option<f64>, WalletError>` - Time-weighted average, or None if the wallet has
+///   fewer than two transactions or no time elapsed between them
+pub fn time_weighted_average_balance(
+    transactions: &[Transaction],
+    wallet: &str,
+) -> Result<Option<f64>, WalletError> {
+    let balances = running_balances(transactions, wallet)?;
+    if balances.len() < 2 {
+        return Ok(None);
+    }
+
+    let mut weighted_sum = 0.0;
+    let mut total_millis = 0.0;
+
+    // Weight each balance by the time until the next transaction
+    for pair in balances.windows(2) {
+        let (start_index, balance) = pair[0];
+        let (end_index, _) = pair[1];
+        let held = transactions[end_index].timestamp - transactions[start_index].timestamp;
+        let millis = held.num_milliseconds() as f64;
+        weighted_sum += balance as f64 * millis;
+        total_millis += millis;
+    }
+
+    if total_millis <= 0.0 {
+        return Ok(None);
+    }
+
+    let average = weighted_sum / total_millis;
+    info!("Time-weighted average balance for wallet {}: {}", wallet, average);
+    Ok(Some(average))
+}
+
+/// Sums the amounts of a wallet's transactions of a single type
+///
+/// Amounts are added as positive magnitudes regardless of their effect on the
+/// balance, answering questions like "how much did I ever deposit". Every
+/// transfer matches a `Transfer` type, whatever its destination.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to sum for
+/// * `only` - Transaction type to include
+///
+/// # Returns
+/// * `i64` - Total amount of the matching transactions, saturating at `i64::MAX`
+pub fn balance_of_type(transactions: &[Transaction], wallet: &str, only: &TransactionType) -> i64 {
+    transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet))
+        .filter(|tx| std::mem::discriminant(&tx.transaction_type) == std::mem::discriminant(only))
+        .fold(0i64, |total, tx| total.saturating_add(tx.amount.minor_units().saturating_abs()))
+}
+
+/// Totals the fees charged across every wallet in the system
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+///
+/// # Returns
+/// * `i64` - Sum of all transaction fees, saturating at the i64 bounds since a total
+///   across wallets can exceed what any single wallet holds
+pub fn total_fee_revenue(transactions: &[Transaction]) -> i64 {
+    let revenue = transactions.iter().fold(0i64, |total, tx| total.saturating_add(tx.fee));
+    info!("Total fee revenue across {} transactions: {}", transactions.len(), revenue);
+    revenue
+}
+
+/// Totals the funds a wallet received through transfers from a specific source wallet
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the receiving wallet
+/// * `source` - Address of the sending wallet
+///
+/// # Returns
+/// * `i64` - Sum of transfer amounts from `source` into `wallet`, saturating at the i64
+///   bounds since funds can be passed back and forth more times than a balance holds
+pub fn inbound_from(transactions: &[Transaction], wallet: &str, source: &str) -> i64 {
+    transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == source)
+        .filter(|tx| matches!(&tx.transaction_type, TransactionType::Transfer { to } if to == wallet))
+        .fold(0i64, |total, tx| total.saturating_add(tx.amount.minor_units()))
+}
+
+/// Flags a wallet's deposits immediately undone by a withdrawal of the same amount, or vice versa
+///
+/// Such back-to-back pairs usually indicate a test entry or a mistaken double entry.
+/// Each transaction belongs to at most one flagged pair.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to scan
+///
+/// # Returns
+/// * `Vec<(usize, usize)>` - Index pairs of the offsetting transactions
+pub fn find_wash_pairs(transactions: &[Transaction], wallet: &str) -> Vec<(usize, usize)> {
+    use TransactionType::*;
+
+    let wallet_indices: Vec<usize> = transactions
+        .iter()
+        .enumerate()
+        .filter(|(_, tx)| tx.involves(wallet))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut pairs = Vec::new();
+    let mut position = 0;
+    while position + 1 < wallet_indices.len() {
+        let (first, second) = (wallet_indices[position], wallet_indices[position + 1]);
+        let (a, b) = (&transactions[first], &transactions[second]);
+        let offsetting = matches!(
+            (&a.transaction_type, &b.transaction_type),
+            (Deposit, Withdrawal) | (Withdrawal, Deposit)
+        );
+
+        if offsetting && a.amount == b.amount {
+            info!("Wash pair in wallet {}: transactions {} and {}", wallet, first, second);
+            pairs.push((first, second));
+            position += 2;
+        } else {
+            position += 1;
+        }
+    }
+
+    pairs
+}
+
+/// Calculates a wallet's balance with interest compounded on each deposit's anniversaries
+///
+/// Withdrawals, outgoing transfers and fees consume the oldest funds first. Each
+/// incoming lot (deposit or received transfer) then earns interest at
+/// `annual_rate_bps` on what is left of it for each full year between its timestamp
+/// and `as_of`, compounding on the anniversary.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `annual_rate_bps` - Annual interest rate in basis points (100 bps = 1%)
+/// * `as_of` - Moment the balance is evaluated at
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Balance including accrued interest, saturating at `i64::MAX`
+pub fn balance_with_anniversary_interest(
+    transactions: &[Transaction],
+    wallet: &str,
+    annual_rate_bps: u32,
+    as_of: DateTime<Local>,
+) -> Result<i64, WalletError> {
+    let balance = calculate_wallet_balance(transactions, wallet)?;
+
+    let mut interest: i128 = 0;
+    for (lot, remaining) in fifo_lots(transactions, wallet)? {
+        // Compound once per full year the lot has been held, stopping once past i64
+        let years = as_of.years_since(lot.timestamp).unwrap_or(0);
+        let mut value = remaining as i128;
+        for _ in 0..years {
+            if value > i64::MAX as i128 {
+                break;
+            }
+            value = value * (10_000 + annual_rate_bps as i128) / 10_000;
+        }
+        interest += value - remaining as i128;
+    }
+
+    let total = i64::try_from(balance as i128 + interest).unwrap_or(i64::MAX);
+    info!(
+        "Balance with anniversary interest for wallet {} as of {}: {}",
+        wallet, as_of, total
+    );
+    Ok(total)
+}
+
+/// Calculates the Pearson correlation coefficient of two equally long series
+///
+/// Returns None for fewer than two points or when either series has no variance.
+fn pearson_correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len();
+    if n < 2 || n != ys.len() {
+        return None;
+    }
+
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_x.sqrt() * variance_y.sqrt()))
+}
+
+/// Calculates how closely two wallets' balances move together over time
+///
+/// Both running balances are sampled at each distinct timestamp where either wallet
+/// transacts, after applying every transaction at that moment, and the Pearson
+/// correlation of the two series is returned.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process, in chronological order
+/// * `wallet_a` - Address of the first wallet
+/// * `wallet_b` - Address of the second wallet
+///
+/// # Returns
+/// * `Result<Option<f64>, WalletError>` - Correlation in [-1, 1], or None if there are
+///   fewer than two samples or either balance never changes
+pub fn balance_correlation(
+    transactions: &[Transaction],
+    wallet_a: &str,
+    wallet_b: &str,
+) -> Result<Option<f64>, WalletError> {
+    let mut tracker_a = BalanceTracker::new(wallet_a);
+    let mut tracker_b = BalanceTracker::new(wallet_b);
+    let (mut series_a, mut series_b) = (Vec::new(), Vec::new());
+
+    let relevant: Vec<&Transaction> = transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet_a) || tx.involves(wallet_b))
+        .collect();
+
+    for (position, tx) in relevant.iter().enumerate() {
+        if tx.involves(wallet_a) {
+            tracker_a.apply(tx)?;
+        }
+        if tx.involves(wallet_b) {
+            tracker_b.apply(tx)?;
+        }
+
+        // Sample once every transaction sharing this timestamp has been applied
+        let moment_complete = relevant
+            .get(position + 1)
+            .is_none_or(|next| next.timestamp != tx.timestamp);
+        if moment_complete {
+            series_a.push(tracker_a.minor_units() as f64);
+            series_b.push(tracker_b.minor_units() as f64);
         }
-        println!("{} | Running balance: {}", tx, balance);
+    }
+
+    let correlation = pearson_correlation(&series_a, &series_b);
+    info!(
+        "Balance correlation between {} and {}: {:?}",
+        wallet_a, wallet_b, correlation
+    );
+    Ok(correlation)
+}
+
+/// Funds a transaction takes out of a wallet, as counted against a daily withdrawal limit
+///
+/// Withdrawals and transfers to another wallet count their amount, and every
+/// transaction the wallet pays for counts its fee.
+pub(crate) fn daily_outflow(tx: &Transaction, wallet: &str) -> i128 {
+    if tx.wallet_address != wallet {
+        return 0;
+    }
+    let leaves_wallet = match &tx.transaction_type {
+        TransactionType::Withdrawal => true,
+        TransactionType::Transfer { to } => to != wallet,
+        TransactionType::Deposit => false,
+    };
+    let amount = if leaves_wallet { tx.amount.minor_units() as i128 } else { 0 };
+    amount + tx.fee as i128
+}
+
+/// Checks that a wallet never withdrew more than a limit within a single calendar day
+///
+/// Outgoing transfers and fees count towards the day's total alongside withdrawals.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to check
+/// * `wallet` - Address of the wallet to check
+/// * `daily_limit` - Maximum total withdrawal amount allowed per day
+///
+/// # Returns
+/// * `Result<(), Vec<NaiveDate>>` - Success, or the sorted local dates on which the
+///   wallet's total outflow exceeded the limit
+pub fn validate_daily_withdrawal_limit(
+    transactions: &[Transaction],
+    wallet: &str,
+    daily_limit: i64,
+) -> Result<(), Vec<NaiveDate>> {
+    // Summed in i128 so a day of large withdrawals cannot wrap
+    let mut daily_totals: BTreeMap<NaiveDate, i128> = BTreeMap::new();
+    for tx in transactions {
+        let outflow = daily_outflow(tx, wallet);
+        if outflow > 0 {
+            *daily_totals.entry(tx.timestamp.date_naive()).or_insert(0) += outflow;
+        }
+    }
+
+    let breaches: Vec<NaiveDate> = daily_totals
+        .into_iter()
+        .filter(|(_, total)| *total > daily_limit as i128)
+        .map(|(date, _)| date)
+        .collect();
+
+    if breaches.is_empty() {
+        Ok(())
+    } else {
+        error!(
+            "Wallet {} exceeded daily withdrawal limit of {} on {:?}",
+            wallet, daily_limit, breaches
+        );
+        Err(breaches)
+    }
+}
+
+/// Counts the distinct wallets a wallet has exchanged transfers with
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to count counterparties for
+///
+/// # Returns
+/// * `usize` - Number of distinct wallets sent funds to or received funds from
+pub fn counterparty_count(transactions: &[Transaction], wallet: &str) -> usize {
+    let mut counterparties: HashSet<&str> = HashSet::new();
+
+    for tx in transactions {
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            if tx.wallet_address == wallet && to != wallet {
+                counterparties.insert(to);
+            } else if to == wallet && tx.wallet_address != wallet {
+                counterparties.insert(&tx.wallet_address);
+            }
+        }
+    }
+
+    counterparties.len()
+}
+
+/// Builds a text statement of a wallet's activity during one calendar month
+///
+/// Transactions before the month roll into the opening balance, transactions after
+/// it are ignored, and the full history is still validated.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process, in chronological order
+/// * `wallet` - Address of the wallet to report on
+/// * `year` - Calendar year of the statement
+/// * `month` - Calendar month of the statement (1-12)
+///
+/// # Returns
+/// * `Result<String, WalletError>` - Statement text or error if validation fails
+pub fn monthly_statement(
+    transactions: &[Transaction],
+    wallet: &str,
+    year: i32,
+    month: u32,
+) -> Result<String, WalletError> {
+    let target = (year, month);
+    let mut opening = 0;
+    let mut closing = 0;
+    let mut lines = Vec::new();
+
+    for (index, balance) in running_balances(transactions, wallet)? {
+        let tx = &transactions[index];
+        let period = (tx.timestamp.year(), tx.timestamp.month());
+        if period < target {
+            opening = balance;
+            closing = balance;
+        } else if period == target {
+            lines.push(format!("{} | Running balance: {}", tx, Money::from_minor(balance)));
+            closing = balance;
+        }
+    }
+
+    let mut statement = format!("Statement for wallet {} - {:04}-{:02}\n", wallet, year, month);
+    statement.push_str(&format!("Opening balance: {}\n", Money::from_minor(opening)));
+    for line in &lines {
+        statement.push_str(line);
+        statement.push('\n');
+    }
+    statement.push_str(&format!("Closing balance: {}\n", Money::from_minor(closing)));
+
+    info!(
+        "Generated statement for wallet {} {:04}-{:02} with {} transactions",
+        wallet, year, month, lines.len()
+    );
+    Ok(statement)
+}
+
+/// Calculates the per-period interest rate needed to grow a balance to a goal
+///
+/// # Arguments
+/// * `current` - Starting balance
+/// * `goal` - Target balance
+/// * `periods` - Number of compounding periods available
+///
+/// # Returns
+/// * `Option<u32>` - Rate in basis points per period, rounded to the nearest basis point,
+///   or None if the goal is below the current balance, the current balance is not
+///   positive, or there are no periods
+pub fn required_rate_bps(current: i64, goal: i64, periods: u32) -> Option<u32> {
+    if current <= 0 || goal < current || periods == 0 {
+        return None;
+    }
+
+    let growth = goal as f64 / current as f64;
+    let rate = growth.powf(1.0 / periods as f64) - 1.0;
+    let bps = (rate * 10_000.0).round();
+
+    if bps > u32::MAX as f64 {
+        return None;
+    }
+    Some(bps as u32)
+}
+
+/// Calculates how many fixed recurring deposits are needed to reach a goal balance
+///
+/// # Arguments
+/// * `recurring_deposit` - Amount added by each deposit
+/// * `current` - Starting balance
+/// * `goal` - Target balance
+///
+/// # Returns
+/// * `Option<u64>` - Number of deposits required (0 if already at or above the goal),
+///   or None if the deposit amount is not positive
+pub fn transactions_to_goal(recurring_deposit: i64, current: i64, goal: i64) -> Option<u64> {
+    if current >= goal {
+        return Some(0);
+    }
+    if recurring_deposit <= 0 {
+        return None;
+    }
+
+    // Widen before subtracting so extreme inputs cannot overflow
+    let remaining = goal as i128 - current as i128;
+    let deposits = (remaining + recurring_deposit as i128 - 1) / recurring_deposit as i128;
+    u64::try_from(deposits).ok()
+}
+
+/// Calculates the balance of every wallet in a single pass over the transactions
+///
+/// Each wallet is validated exactly as `calculate_wallet_balance` would, with
+/// transfers debiting the source and crediting the destination.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+///
+/// # Returns
+/// * `Result<HashMap<String, i64>, WalletError>` - Balance of every wallet, or a
+///   `WalletValidation` error naming the first wallet whose history is invalid
+pub fn calculate_all_balances(transactions: &[Transaction]) -> Result<HashMap<String, i64>, WalletError> {
+    let mut trackers: HashMap<&str, BalanceTracker> = HashMap::new();
+
+    for tx in transactions {
+        let mut affected = vec![tx.wallet_address.as_str()];
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            if to != &tx.wallet_address {
+                affected.push(to.as_str());
+            }
+        }
+        for wallet in affected {
+            trackers
+                .entry(wallet)
+                .or_insert_with(|| BalanceTracker::new(wallet))
+                .apply(tx)
+                .map_err(|e| WalletError::WalletValidation {
+                    wallet: wallet.to_string(),
+                    source: Box::new(e),
+                })?;
+        }
+    }
+
+    info!("Calculated balances for {} wallets in one pass", trackers.len());
+    Ok(trackers
+        .into_iter()
+        .map(|(wallet, tracker)| (wallet.to_string(), tracker.minor_units()))
+        .collect())
+}
+
+/// Ranks every wallet by its current balance
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+///
+/// # Returns
+/// * `Result<Vec<(String, i64)>, WalletError>` - Wallet addresses with their balances,
+///   highest balance first and ties broken by address, or the first validation error
+pub fn wallets_by_balance(transactions: &[Transaction]) -> Result<Vec<(String, i64)>, WalletError> {
+    let mut rankings: Vec<(String, i64)> = calculate_all_balances(transactions)?.into_iter().collect();
+
+    rankings.sort_by(|(a_address, a_balance), (b_address, b_balance)| {
+        b_balance.cmp(a_balance).then_with(|| a_address.cmp(b_address))
+    });
+    Ok(rankings)
+}
+
+/// Calculates a wallet's balance as if selected transactions had been reversed
+///
+/// Each reversed transaction keeps its position but has its effect negated, so a
+/// reversed deposit must still be covered by the funds available at that point.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `reverse_ids` - Identifiers of the transactions to reverse
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Resulting balance or error if the adjusted history is invalid
+pub fn balance_if_reversed(
+    transactions: &[Transaction],
+    wallet: &str,
+    reverse_ids: &HashSet<u64>,
+) -> Result<i64, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet);
+
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        if reverse_ids.contains(&tx.id) {
+            info!("Reversing transaction {} for wallet {}", tx.id, wallet);
+            tracker.apply(&tx.reversed())?;
+        } else {
+            tracker.apply(tx)?;
+        }
+    }
+
+    info!("Balance for wallet {} with reversals: {}", wallet, tracker.minor_units());
+    Ok(tracker.minor_units())
+}
+
+/// Finds the wallet involved in the most transactions
+///
+/// Transfers count toward both the sending and the receiving wallet.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+///
+/// # Returns
+/// * `Option<(String, usize)>` - Busiest wallet address and its transaction count, with
+///   ties going to the alphabetically first address, or None for empty input
+pub fn busiest_wallet(transactions: &[Transaction]) -> Option<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tx in transactions {
+        *counts.entry(tx.wallet_address.as_str()).or_insert(0) += 1;
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            if to != &tx.wallet_address {
+                *counts.entry(to.as_str()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    // Iterating in address order keeps the first address among equal counts
+    let mut busiest: Option<(&str, usize)> = None;
+    for (address, count) in counts {
+        if busiest.is_none_or(|(_, best)| count > best) {
+            busiest = Some((address, count));
+        }
+    }
+    busiest.map(|(address, count)| (address.to_string(), count))
+}
+
+/// Checks a proposed withdrawal against a stack of policies
+///
+/// Policies are evaluated in order and the first one that fails determines the error.
+///
+/// # Arguments
+/// * `transactions` - Existing transaction history
+/// * `wallet` - Address of the wallet withdrawing funds
+/// * `amount` - Amount the wallet wants to withdraw
+/// * `fee` - Fee charged on top of the withdrawal
+/// * `as_of` - Moment the withdrawal would be made, selecting the day `DailyLimit` checks
+/// * `policies` - Policies the withdrawal must satisfy
+///
+/// # Returns
+/// * `Result<(), WalletError>` - Success, or the error from the first failing policy
+pub fn validate_withdrawal(
+    transactions: &[Transaction],
+    wallet: &str,
+    amount: i64,
+    fee: i64,
+    as_of: DateTime<Local>,
+    policies: &[WithdrawalPolicy],
+) -> Result<(), WalletError> {
+    if amount < 0 {
+        error!("Invalid withdrawal amount proposed for {}: {}", wallet, amount);
+        return Err(WalletError::InvalidAmount(amount));
+    }
+    if fee < 0 {
+        error!("Invalid withdrawal fee proposed for {}: {}", wallet, fee);
+        return Err(WalletError::InvalidAmount(fee));
+    }
+
+    for policy in policies {
+        match policy {
+            WithdrawalPolicy::SufficientFunds => {
+                let available = calculate_wallet_balance(transactions, wallet)?;
+                let charge = amount.checked_add(fee).ok_or_else(|| WalletError::BalanceOverflow {
+                    wallet: wallet.to_string(),
+                })?;
+                if available < charge {
+                    return Err(WalletError::InsufficientFunds {
+                        requested: charge,
+                        available,
+                    });
+                }
+            }
+            WithdrawalPolicy::MaxSingle(limit) => {
+                if amount > *limit {
+                    return Err(WalletError::AmountExceedsLimit {
+                        amount,
+                        limit: *limit,
+                    });
+                }
+            }
+            WithdrawalPolicy::DailyLimit(limit) => {
+                // Summed in i128 so a long day of large withdrawals cannot wrap
+                let date = as_of.date_naive();
+                let withdrawn: i128 = transactions
+                    .iter()
+                    .filter(|tx| tx.timestamp.date_naive() == date)
+                    .map(|tx| daily_outflow(tx, wallet))
+                    .sum();
+                if withdrawn + amount as i128 + fee as i128 > *limit as i128 {
+                    return Err(WalletError::DailyLimitExceeded {
+                        date,
+                        limit: *limit,
+                    });
+                }
+            }
+            WithdrawalPolicy::NotFrozen(frozen) => {
+                if frozen.contains(wallet) {
+                    return Err(WalletError::WalletFrozen(wallet.to_string()));
+                }
+            }
+        }
+    }
+
+    info!("Withdrawal of {} from {} passed {} policies", amount, wallet, policies.len());
+    Ok(())
+}
+
+/// Computes a tamper-evident hash chain over a transaction history
+///
+/// Each link hashes the previous link together with every field of the
+/// transaction, so altering any transaction changes its own link and every
+/// link after it.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to chain, in order
+///
+/// # Returns
+/// * `Vec<String>` - Hex-encoded SHA-256 link for each transaction
+pub fn chain_hashes(transactions: &[Transaction]) -> Vec<String> {
+    let mut links = Vec::with_capacity(transactions.len());
+    let mut previous = String::new();
+
+    for tx in transactions {
+        let destination = match &tx.transaction_type {
+            TransactionType::Transfer { to } => to.as_str(),
+            _ => "",
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(previous.as_bytes());
+        hasher.update(
+            format!(
+                "|{}|{}|{}|{}|{}|{}|{}|{}|{}|{:?}|{}",
+                tx.id,
+                tx.transaction_type,
+                destination,
+                tx.wallet_address,
+                tx.amount.minor_units(),
+                tx.currency,
+                tx.fee,
+                tx.timestamp.to_rfc3339(),
+                tx.memo.as_deref().unwrap_or(""),
+                tx.status,
+                tx.idempotency_key.as_deref().unwrap_or(""),
+            )
+            .as_bytes(),
+        );
+        previous = format!("{:x}", hasher.finalize());
+        links.push(previous.clone());
+    }
+
+    links
+}
+
+/// Calculates a wallet's balance when deposited funds expire after a fixed time
+///
+/// Withdrawals consume the oldest deposits first; whatever remains of a deposit
+/// older than `expiry` at `as_of` no longer counts toward the balance.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process, in chronological order
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `expiry` - How long deposited funds remain available
+/// * `as_of` - Moment the balance is evaluated at
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Unexpired balance or error if validation fails
+pub fn balance_with_expiry(
+    transactions: &[Transaction],
+    wallet: &str,
+    expiry: Duration,
+    as_of: DateTime<Local>,
+) -> Result<i64, WalletError> {
+    let cutoff = as_of - expiry;
+    let balance = fifo_lots(transactions, wallet)?
+        .into_iter()
+        .filter(|(lot, _)| lot.timestamp >= cutoff)
+        .map(|(_, remaining)| remaining)
+        .sum();
+
+    info!("Unexpired balance for wallet {} as of {}: {}", wallet, as_of, balance);
+    Ok(balance)
+}
+
+/// Calculates the smallest per-period deposit that keeps a balance from going negative
+///
+/// Each period receives the deposit before the withdrawal is taken, so the starting
+/// balance can cover part of the total drawdown.
+///
+/// # Arguments
+/// * `withdrawals_per_period` - Amount withdrawn every period
+/// * `periods` - Number of periods to sustain
+/// * `starting` - Balance before the first period
+///
+/// # Returns
+/// * `i64` - Minimum deposit per period (0 if the starting balance already suffices)
+pub fn sustainable_deposit(withdrawals_per_period: i64, periods: u32, starting: i64) -> i64 {
+    if periods == 0 {
+        return 0;
+    }
+
+    // The balance is lowest after the final period, so only that one binds
+    let periods = periods as i128;
+    let shortfall = periods * withdrawals_per_period as i128 - starting as i128;
+    if shortfall <= 0 {
+        return 0;
+    }
+    let deposit = (shortfall + periods - 1) / periods;
+    i64::try_from(deposit).unwrap_or(i64::MAX)
+}
+
+/// Groups batch import errors by kind for a quick data quality overview
+///
+/// # Arguments
+/// * `errors` - Record indices paired with the error each one produced
+///
+/// # Returns
+/// * `ErrorSummary` - Failing indices grouped by error kind
+pub fn summarize_errors(errors: &[(usize, WalletError)]) -> ErrorSummary {
+    let mut summary = ErrorSummary::default();
+    for (index, error) in errors {
+        summary
+            .by_kind
+            .entry(error.kind().to_string())
+            .or_default()
+            .push(*index);
+    }
+
+    info!("Summarized {} errors across {} kinds", summary.total(), summary.by_kind.len());
+    summary
+}
+
+/// Projects a balance that earns interest while funding scheduled withdrawals
+///
+/// Each period applies interest first and then subtracts the withdrawal. Once the
+/// balance is depleted it stays at zero.
+///
+/// # Arguments
+/// * `initial` - Starting balance
+/// * `rate_bps` - Interest per period in basis points (100 bps = 1%)
+/// * `withdrawal_per_period` - Amount withdrawn after interest each period
+/// * `periods` - Number of periods to project
+///
+/// # Returns
+/// * `Vec<i64>` - Balance at the end of each period
+pub fn project_with_drawdown(
+    initial: i64,
+    rate_bps: u32,
+    withdrawal_per_period: i64,
+    periods: usize,
+) -> Vec<i64> {
+    let mut balance = initial.max(0) as i128;
+    let mut projection = Vec::with_capacity(periods);
+
+    for _ in 0..periods {
+        if balance > 0 {
+            balance = balance * (10_000 + rate_bps as i128) / 10_000;
+            balance = (balance - withdrawal_per_period as i128).max(0);
+        }
+        projection.push(i64::try_from(balance).unwrap_or(i64::MAX));
+    }
+
+    projection
+}
+
+/// Calculates a wallet's balance as if one transaction had a different amount
+///
+/// The input is left untouched. A negative replacement amount is rejected up front,
+/// even when the transaction belongs to another wallet; the modified history is then
+/// fully revalidated, so a resulting overdraft is reported as an error.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `id` - Identifier of the transaction to modify
+/// * `new_amount` - Amount to use for that transaction
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Resulting balance, `InvalidAmount` for a negative
+///   `new_amount`, `TransactionNotFound` for an unknown identifier, or the validation
+///   error of the modified history
+pub fn balance_with_modified_amount(
+    transactions: &[Transaction],
+    wallet: &str,
+    id: u64,
+    new_amount: i64,
+) -> Result<i64, WalletError> {
+    if new_amount < 0 {
+        error!("Invalid replacement amount {} for transaction {}", new_amount, id);
+        return Err(WalletError::InvalidAmount(new_amount));
+    }
+    if !transactions.iter().any(|tx| tx.id == id) {
+        error!("Cannot modify unknown transaction {}", id);
+        return Err(WalletError::TransactionNotFound(id));
+    }
+
+    let mut tracker = BalanceTracker::new(wallet);
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        if tx.id == id {
+            let modified = Transaction {
+                amount: Money::from_minor(new_amount),
+                ..tx.clone()
+            };
+            tracker.apply(&modified)?;
+        } else {
+            tracker.apply(tx)?;
+        }
+    }
+
+    info!(
+        "Balance for wallet {} with transaction {} set to {}: {}",
+        wallet, id, new_amount, tracker.minor_units()
+    );
+    Ok(tracker.minor_units())
+}
+
+/// Calculates a wallet's balance separately for each currency it holds
+///
+/// Each currency is validated on its own, so funds in one currency cannot cover a
+/// withdrawal in another.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balances for
+///
+/// # Returns
+/// * `Result<BTreeMap<String, i64>, WalletError>` - Balance per currency code
+pub fn balances_by_currency(
+    transactions: &[Transaction],
+    wallet: &str,
+) -> Result<BTreeMap<String, i64>, WalletError> {
+    let mut trackers: BTreeMap<&str, BalanceTracker> = BTreeMap::new();
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        trackers
+            .entry(tx.currency.as_str())
+            .or_insert_with(|| BalanceTracker::new(wallet))
+            .apply(tx)?;
+    }
+
+    Ok(trackers
+        .into_iter()
+        .map(|(currency, tracker)| (currency.to_string(), tracker.minor_units()))
+        .collect())
+}
+
+/// Converts a wallet's per-currency balances into one base currency total
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to value
+/// * `rates` - Value of one unit of each currency in the base currency
+///
+/// # Returns
+/// * `Result<f64, WalletError>` - Total in the base currency, or `MissingExchangeRate`
+///   if a currency the wallet holds has no rate
+pub fn balance_in_base(
+    transactions: &[Transaction],
+    wallet: &str,
+    rates: &HashMap<String, f64>,
+) -> Result<f64, WalletError> {
+    let mut total = 0.0;
+    for (currency, balance) in balances_by_currency(transactions, wallet)? {
+        let rate = rates.get(&currency).ok_or_else(|| {
+            error!("No exchange rate supplied for {} held by {}", currency, wallet);
+            WalletError::MissingExchangeRate(currency.clone())
+        })?;
+        total += balance as f64 * rate;
+    }
+
+    info!("Base currency balance for wallet {}: {}", wallet, total);
+    Ok(total)
+}
+
+/// Flags transactions whose amount is unusually far from a wallet's typical amount
+///
+/// Uses the z-score of each amount against the mean and population standard
+/// deviation of the wallet's transaction amounts.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to scan
+/// * `z_threshold` - Number of standard deviations beyond which an amount is an outlier
+///
+/// # Returns
+/// * `Result<Vec<usize>, WalletError>` - Indices of outlier transactions (empty if all
+///   amounts are equal), or error if the wallet's history is invalid
+pub fn amount_outliers(
+    transactions: &[Transaction],
+    wallet: &str,
+    z_threshold: f64,
+) -> Result<Vec<usize>, WalletError> {
+    let entries = running_balances(transactions, wallet)?;
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let amounts: Vec<f64> = entries
+        .iter()
+        .map(|(index, _)| transactions[*index].amount.minor_units() as f64)
+        .collect();
+    let mean = amounts.iter().sum::<f64>() / amounts.len() as f64;
+    let variance = amounts.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / amounts.len() as f64;
+    let std_dev = variance.sqrt();
+
+    // Identical amounts have no spread, so nothing stands out
+    if std_dev == 0.0 {
+        return Ok(Vec::new());
+    }
+
+    let outliers: Vec<usize> = entries
+        .iter()
+        .zip(&amounts)
+        .filter(|(_, amount)| ((*amount - mean) / std_dev).abs() > z_threshold)
+        .map(|((index, _), _)| *index)
+        .collect();
+
+    info!("Found {} amount outliers for wallet {}", outliers.len(), wallet);
+    Ok(outliers)
+}
+
+/// Adds up one value per transaction, refusing to wrap past the i64 range
+///
+/// A total that no longer fits is reported as `BalanceOverflow` for the wallet
+/// of the transaction that pushed it over.
+fn checked_total<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    value: impl Fn(&Transaction) -> i64,
+) -> Result<i64, WalletError> {
+    transactions.into_iter().try_fold(0i64, |total, tx| {
+        total.checked_add(value(tx)).ok_or_else(|| {
+            error!("Total overflowed at transaction {} of wallet {}", tx.id, tx.wallet_address);
+            WalletError::BalanceOverflow {
+                wallet: tx.wallet_address.clone(),
+            }
+        })
+    })
+}
+
+/// Read-only snapshot of a transaction log for audits and sharing
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    /// Current balance of every wallet
+    pub balances: BTreeMap<String, i64>,
+    /// Number of transactions in the log
+    pub transaction_count: usize,
+    /// Number of distinct wallets in the log
+    pub wallet_count: usize,
+    /// Sum of all deposit amounts
+    pub total_deposited: i64,
+    /// Sum of all withdrawal amounts
+    pub total_withdrawn: i64,
+    /// Sum of all transfer amounts
+    pub total_transferred: i64,
+    /// Sum of all transaction fees
+    pub total_fees: i64,
+    /// Final link of the audit hash chain, or None for an empty log
+    pub history_hash: Option<String>,
+}
+
+/// Bundles balances, global statistics, and the history hash into one report
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to report on
+///
+/// # Returns
+/// * `Result<Report, WalletError>` - Assembled report, the validation error if any wallet's
+///   history is invalid, or `BalanceOverflow` if a system-wide total does not fit in an i64
+pub fn build_report(transactions: &[Transaction]) -> Result<Report, WalletError> {
+    let balances: BTreeMap<String, i64> = calculate_all_balances(transactions)?.into_iter().collect();
+
+    // Every wallet's balance may fit while the total across wallets does not
+    let total_of = |kind: TransactionType| {
+        checked_total(
+            transactions
+                .iter()
+                .filter(|tx| std::mem::discriminant(&tx.transaction_type) == std::mem::discriminant(&kind)),
+            |tx| tx.amount.minor_units(),
+        )
+    };
+
+    let report = Report {
+        wallet_count: balances.len(),
+        balances,
+        transaction_count: transactions.len(),
+        total_deposited: total_of(TransactionType::Deposit)?,
+        total_withdrawn: total_of(TransactionType::Withdrawal)?,
+        total_transferred: total_of(TransactionType::Transfer { to: String::new() })?,
+        total_fees: checked_total(transactions, |tx| tx.fee)?,
+        history_hash: chain_hashes(transactions).pop(),
+    };
+
+    info!(
+        "Built report covering {} transactions across {} wallets",
+        report.transaction_count, report.wallet_count
+    );
+    Ok(report)
+}
+
+/// Breakdown of a wallet's settled and unsettled funds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementReport {
+    /// Balance from confirmed transactions only
+    pub confirmed_balance: i64,
+    /// Funds arriving through pending transactions
+    pub pending_inflow: i64,
+    /// Funds leaving through pending transactions
+    pub pending_outflow: i64,
+    /// Balance once every pending transaction settles
+    pub projected_balance: i64,
+}
+
+/// Separates a wallet's confirmed balance from the impact of pending transactions
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to report on
+///
+/// # Returns
+/// * `Result<SettlementReport, WalletError>` - Settlement breakdown or error if either the
+///   confirmed or the full history is invalid
+pub fn settlement_report(
+    transactions: &[Transaction],
+    wallet: &str,
+) -> Result<SettlementReport, WalletError> {
+    let projected_balance = calculate_wallet_balance(transactions, wallet)?;
+
+    let mut confirmed = BalanceTracker::new(wallet);
+    let (mut pending_inflow, mut pending_outflow) = (0, 0);
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        match tx.status {
+            TransactionStatus::Confirmed => {
+                confirmed.apply(tx)?;
+            }
+            TransactionStatus::Pending => {
+                let delta = tx.balance_delta(wallet);
+                if delta > 0 {
+                    pending_inflow += delta;
+                } else {
+                    pending_outflow -= delta;
+                }
+            }
+        }
+    }
+
+    let report = SettlementReport {
+        confirmed_balance: confirmed.minor_units(),
+        pending_inflow,
+        pending_outflow,
+        projected_balance,
+    };
+    info!("Settlement report for wallet {}: {:?}", wallet, report);
+    Ok(report)
+}
+
+/// Finds a wallet's largest withdrawal
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to scan
+///
+/// # Returns
+/// * `Option<&Transaction>` - Withdrawal with the greatest amount, the earliest one on
+///   ties, or None if the wallet has no withdrawals
+pub fn largest_withdrawal<'a>(transactions: &'a [Transaction], wallet: &str) -> Option<&'a Transaction> {
+    transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == wallet)
+        .filter(|tx| matches!(tx.transaction_type, TransactionType::Withdrawal))
+        .fold(None, |largest: Option<&Transaction>, tx| match largest {
+            Some(current) if current.amount >= tx.amount => Some(current),
+            _ => Some(tx),
+        })
+}
+
+/// Totals a wallet's deposits and withdrawals within a time window
+///
+/// Both window bounds are inclusive. Transfers are left out so the totals
+/// can be reconciled against external deposit and withdrawal records.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to total
+/// * `from` - Start of the window
+/// * `to` - End of the window
+///
+/// # Returns
+/// * `(i64, i64)` - Total deposited and total withdrawn within the window
+pub fn window_parity(
+    transactions: &[Transaction],
+    wallet: &str,
+    from: DateTime<Local>,
+    to: DateTime<Local>,
+) -> (i64, i64) {
+    let mut deposited = 0;
+    let mut withdrawn = 0;
+    for tx in transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == wallet)
+        .filter(|tx| tx.timestamp >= from && tx.timestamp <= to)
+    {
+        match tx.transaction_type {
+            TransactionType::Deposit => deposited += tx.amount.minor_units(),
+            TransactionType::Withdrawal => withdrawn += tx.amount.minor_units(),
+            TransactionType::Transfer { .. } => {}
+        }
+    }
+    info!(
+        "Window parity for wallet {} from {} to {}: deposited {}, withdrawn {}",
+        wallet, from, to, deposited, withdrawn
+    );
+    (deposited, withdrawn)
+}
+
+/// Compares a wallet's actual deposits against a recurring deposit schedule
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet on the schedule
+/// * `expected_per_period` - Amount expected to be deposited each period
+/// * `periods` - Number of periods elapsed
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Expected total minus actual deposits, positive when
+///   under-deposited, `InvalidAmount` if the expected amount is negative, or
+///   `BalanceOverflow` if a total or the variance does not fit in an i64
+pub fn schedule_variance(
+    transactions: &[Transaction],
+    wallet: &str,
+    expected_per_period: i64,
+    periods: u32,
+) -> Result<i64, WalletError> {
+    if expected_per_period < 0 {
+        return Err(WalletError::InvalidAmount(expected_per_period));
+    }
+    let overflow = || {
+        error!("Schedule totals for wallet {} overflow", wallet);
+        WalletError::BalanceOverflow {
+            wallet: wallet.to_string(),
+        }
+    };
+    let expected = expected_per_period
+        .checked_mul(i64::from(periods))
+        .ok_or_else(overflow)?;
+
+    let actual = transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == wallet)
+        .filter(|tx| matches!(tx.transaction_type, TransactionType::Deposit))
+        .try_fold(0i64, |total, tx| total.checked_add(tx.amount.minor_units()))
+        .ok_or_else(overflow)?;
+
+    let variance = expected.checked_sub(actual).ok_or_else(overflow)?;
+    info!(
+        "Schedule variance for wallet {}: expected {}, actual {}, variance {}",
+        wallet, expected, actual, variance
+    );
+    Ok(variance)
+}
+
+/// Finds expected recurring periods that have no transaction near their due time
+///
+/// Period `i` is due at `start + i * interval` and is satisfied by any of the
+/// wallet's transactions within half an interval of that time, so each
+/// transaction can only land in one period's window. A period whose due time
+/// cannot be represented has no transaction near it and is reported as missing.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet making the recurring transactions
+/// * `start` - Due time of the first period
+/// * `interval` - Time between periods
+/// * `count` - Number of periods expected
+///
+/// # Returns
+/// * `Vec<usize>` - Indices of the periods with no matching transaction
+pub fn missing_periods(
+    transactions: &[Transaction],
+    wallet: &str,
+    start: DateTime<Local>,
+    interval: Duration,
+    count: usize,
+) -> Vec<usize> {
+    let tolerance = interval / 2;
+    let mut timestamps: Vec<DateTime<Local>> = transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == wallet)
+        .map(|tx| tx.timestamp)
+        .collect();
+    timestamps.sort_unstable();
+
+    let missing: Vec<usize> = (0..count)
+        .filter(|&period| {
+            let window = i32::try_from(period)
+                .ok()
+                .and_then(|period| interval.checked_mul(period))
+                .and_then(|offset| start.checked_add_signed(offset))
+                .and_then(|due| Some((due.checked_sub_signed(tolerance)?, due.checked_add_signed(tolerance)?)));
+            let Some((window_start, window_end)) = window else {
+                return true;
+            };
+            // First transaction at or after the window start, if it falls inside the window
+            let first = timestamps.partition_point(|timestamp| *timestamp < window_start);
+            timestamps.get(first).is_none_or(|timestamp| *timestamp >= window_end)
+        })
+        .collect();
+    if !missing.is_empty() {
+        info!("Wallet {} missed recurring periods {:?}", wallet, missing);
+    }
+    missing
+}
+
+/// Checks a claimed final balance against the balance computed from a history
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet the claim is about
+/// * `claimed` - Final balance reported by the data source
+///
+/// # Returns
+/// * `Result<bool, WalletError>` - Whether the claim matches, or the validation error
+///   that makes the history itself invalid
+pub fn verify_final_balance(
+    transactions: &[Transaction],
+    wallet: &str,
+    claimed: i64,
+) -> Result<bool, WalletError> {
+    let actual = calculate_wallet_balance(transactions, wallet)?;
+    if actual != claimed {
+        error!(
+            "Claimed balance {} for wallet {} does not match computed balance {}",
+            claimed, wallet, actual
+        );
+    }
+    Ok(actual == claimed)
+}
+
+/// Tracks how many distinct wallets have appeared as time goes on
+///
+/// Transactions are visited in timestamp order, and transfer destinations
+/// count as appearing when the transfer happens.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+///
+/// # Returns
+/// * `Vec<(DateTime<Local>, usize)>` - One entry per transaction with the number of
+///   wallets seen up to and including it
+pub fn cumulative_wallet_count(transactions: &[Transaction]) -> Vec<(DateTime<Local>, usize)> {
+    let mut ordered: Vec<&Transaction> = transactions.iter().collect();
+    ordered.sort_by_key(|tx| tx.timestamp);
+
+    let mut seen = HashSet::new();
+    ordered
+        .into_iter()
+        .map(|tx| {
+            seen.insert(tx.wallet_address.as_str());
+            if let TransactionType::Transfer { to } = &tx.transaction_type {
+                seen.insert(to.as_str());
+            }
+            (tx.timestamp, seen.len())
+        })
+        .collect()
+}
+
+/// Computes the net balance change of a wallet's transactions carrying a tag
+///
+/// A transaction carries a tag when its memo equals the tag exactly.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to categorize
+/// * `tag` - Memo identifying the category
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Money in minus money out across the tagged transactions,
+///   or `BalanceOverflow` if the net change does not fit in an i64
+pub fn balance_change_for_tag(transactions: &[Transaction], wallet: &str, tag: &str) -> Result<i64, WalletError> {
+    let change = transactions
+        .iter()
+        .filter(|tx| tx.memo.as_deref() == Some(tag))
+        .try_fold(0i64, |change, tx| change.checked_add(tx.balance_delta(wallet)))
+        .ok_or_else(|| {
+            error!("Net change tagged '{}' for wallet {} overflows", tag, wallet);
+            WalletError::BalanceOverflow {
+                wallet: wallet.to_string(),
+            }
+        })?;
+    info!("Net change tagged '{}' for wallet {}: {}", tag, wallet, change);
+    Ok(change)
+}
+
+/// Nets a wallet's balance change per category, where a category is the memo prefix
+///
+/// The prefix is the part of the memo before the first separator; a memo
+/// without the separator is its own category. Transactions without a memo
+/// are left out.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to categorize
+/// * `separator` - Character ending the category prefix, e.g. `':'` in `"food:lunch"`
+///
+/// # Returns
+/// * `Result<HashMap<String, i64>, WalletError>` - Money in minus money out per category,
+///   or `BalanceOverflow` if a category's total does not fit in an i64
+pub fn totals_by_memo_prefix(
+    transactions: &[Transaction],
+    wallet: &str,
+    separator: char,
+) -> Result<HashMap<String, i64>, WalletError> {
+    let mut totals: HashMap<String, i64> = HashMap::new();
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        if let Some(memo) = tx.memo.as_deref() {
+            let category = memo.split(separator).next().unwrap_or(memo);
+            let total = totals.entry(category.to_string()).or_default();
+            *total = total.checked_add(tx.balance_delta(wallet)).ok_or_else(|| {
+                error!("Total for memo category '{}' of wallet {} overflows", category, wallet);
+                WalletError::BalanceOverflow {
+                    wallet: wallet.to_string(),
+                }
+            })?;
+        }
+    }
+    info!("Computed {} memo category totals for wallet {}", totals.len(), wallet);
+    Ok(totals)
+}
+
+/// Plans the smallest deposits that keep a balance non-negative through scheduled withdrawals
+///
+/// Withdrawals are applied in chronological order, and a deposit is required
+/// at a withdrawal's time only when the balance cannot cover it.
+///
+/// # Arguments
+/// * `withdrawals` - Scheduled withdrawal times and amounts, in any order
+/// * `starting` - Balance before the first withdrawal
+///
+/// # Returns
+/// * `Result<Vec<(DateTime<Local>, i64)>, WalletError>` - Required deposit times and amounts,
+///   chronologically, or `InvalidAmount` for a negative starting balance or withdrawal amount
+pub fn required_deposits(
+    withdrawals: &[(DateTime<Local>, i64)],
+    starting: i64,
+) -> Result<Vec<(DateTime<Local>, i64)>, WalletError> {
+    if starting < 0 {
+        error!("Invalid starting balance for deposit planning: {}", starting);
+        return Err(WalletError::InvalidAmount(starting));
+    }
+    if let Some(&(_, amount)) = withdrawals.iter().find(|(_, amount)| *amount < 0) {
+        error!("Invalid scheduled withdrawal amount: {}", amount);
+        return Err(WalletError::InvalidAmount(amount));
+    }
+
+    let mut scheduled = withdrawals.to_vec();
+    scheduled.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut balance = starting;
+    let mut deposits = Vec::new();
+    for (timestamp, amount) in scheduled {
+        if amount > balance {
+            let shortfall = amount.saturating_sub(balance);
+            info!("Deposit of {} required at {} to cover withdrawal of {}", shortfall, timestamp, amount);
+            deposits.push((timestamp, shortfall));
+            balance = 0;
+        } else {
+            balance = balance.saturating_sub(amount);
+        }
+    }
+    Ok(deposits)
+}
+
+/// Headline figures for a single wallet's activity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletReport {
+    /// Address of the wallet the figures describe
+    pub wallet_address: String,
+    /// Current balance after replaying the history
+    pub balance: i64,
+    /// Number of transactions involving the wallet
+    pub transaction_count: usize,
+    /// Total money that entered the wallet
+    pub total_in: i64,
+    /// Total money that left the wallet
+    pub total_out: i64,
+}
+
+/// Builds the headline figures for a wallet
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to summarize
+/// * `wallet` - Address of the wallet to report on
+///
+/// # Returns
+/// * `Result<WalletReport, WalletError>` - Wallet figures or error if the history is invalid
+pub fn wallet_report(transactions: &[Transaction], wallet: &str) -> Result<WalletReport, WalletError> {
+    let balance = calculate_wallet_balance(transactions, wallet)?;
+
+    let mut report = WalletReport {
+        wallet_address: wallet.to_string(),
+        balance,
+        transaction_count: 0,
+        total_in: 0,
+        total_out: 0,
+    };
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        report.transaction_count += 1;
+        let delta = tx.balance_delta(wallet);
+        if delta > 0 {
+            report.total_in += delta;
+        } else {
+            report.total_out -= delta;
+        }
+    }
+    info!("Wallet report for {}: {:?}", wallet, report);
+    Ok(report)
+}
+
+/// Renders a wallet's headline figures on a single line
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to summarize
+/// * `wallet` - Address of the wallet to summarize
+///
+/// # Returns
+/// * `Result<String, WalletError>` - Summary such as "wallet_1: bal=70, tx=2, in=100, out=30",
+///   or error if the history is invalid
+pub fn wallet_one_liner(transactions: &[Transaction], wallet: &str) -> Result<String, WalletError> {
+    let report = wallet_report(transactions, wallet)?;
+    Ok(format!(
+        "{}: bal={}, tx={}, in={}, out={}",
+        report.wallet_address, report.balance, report.transaction_count, report.total_in, report.total_out
+    ))
+}
+
+/// Overview of a wallet's activity, extending `WalletReport` with its largest transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletSummary {
+    /// Address of the wallet the figures describe
+    pub wallet_address: String,
+    /// Total money that entered the wallet, including transfers received
+    pub total_deposits: i64,
+    /// Total money that left the wallet, including transfers sent and fees
+    pub total_withdrawals: i64,
+    /// Number of transactions involving the wallet
+    pub transaction_count: usize,
+    /// Current balance after replaying the history
+    pub balance: i64,
+    /// Transaction moving the largest amount, the earliest on ties, if any
+    pub largest_transaction: Option<Transaction>,
+}
+
+/// Builds an activity overview for a wallet
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to summarize
+/// * `wallet_address` - Address of the wallet to summarize
+///
+/// # Returns
+/// * `Result<WalletSummary, WalletError>` - Wallet overview or error if the history is invalid
+pub fn wallet_summary(transactions: &[Transaction], wallet_address: &str) -> Result<WalletSummary, WalletError> {
+    let report = wallet_report(transactions, wallet_address)?;
+    let largest_transaction = transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet_address))
+        .rev()
+        .max_by_key(|tx| tx.amount)
+        .cloned();
+
+    Ok(WalletSummary {
+        wallet_address: report.wallet_address,
+        total_deposits: report.total_in,
+        total_withdrawals: report.total_out,
+        transaction_count: report.transaction_count,
+        balance: report.balance,
+        largest_transaction,
+    })
+}
+
+/// Computes the share of a wallet's current balance that a withdrawal would consume
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet withdrawing
+/// * `amount` - Proposed withdrawal amount
+///
+/// # Returns
+/// * `Result<f64, WalletError>` - `amount / balance` as a fraction, or an error if the
+///   amount is negative, the history is invalid, or the balance is zero
+pub fn withdrawal_ratio(transactions: &[Transaction], wallet: &str, amount: i64) -> Result<f64, WalletError> {
+    if amount < 0 {
+        return Err(WalletError::InvalidAmount(amount));
+    }
+    let balance = calculate_wallet_balance(transactions, wallet)?;
+    if balance == 0 {
+        error!("Cannot compute withdrawal ratio for empty wallet {}", wallet);
+        return Err(WalletError::InsufficientFunds {
+            requested: amount,
+            available: 0,
+        });
+    }
+    Ok(amount as f64 / balance as f64)
+}
+
+/// Computes the additional funds a wallet needs before a withdrawal can succeed
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet withdrawing
+/// * `withdrawal` - Total the withdrawal would take from the wallet, fees included
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Smallest deposit that covers the withdrawal (0 if
+///   the balance already does), or an error if the withdrawal is negative or the
+///   history is invalid
+pub fn shortfall(transactions: &[Transaction], wallet: &str, withdrawal: i64) -> Result<i64, WalletError> {
+    if withdrawal < 0 {
+        return Err(WalletError::InvalidAmount(withdrawal));
+    }
+    let balance = calculate_wallet_balance(transactions, wallet)?;
+    let needed = withdrawal.saturating_sub(balance).max(0);
+    info!("Shortfall for withdrawal of {} from wallet {}: {}", withdrawal, wallet, needed);
+    Ok(needed)
+}
+
+/// Calculates a wallet's balance over only the transactions matching a predicate
+///
+/// Funds checks are enforced over the filtered sequence, so a withdrawal that
+/// was covered by an excluded deposit is reported as insufficient funds.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `predicate` - Filter deciding which transactions are included
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Balance of the filtered history or error if validation fails
+pub fn calculate_balance_filtered(
+    transactions: &[Transaction],
+    wallet: &str,
+    predicate: impl Fn(&Transaction) -> bool,
+) -> Result<i64, WalletError> {
+    let mut tracker = BalanceTracker::new(wallet);
+    for tx in transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet))
+        .filter(|tx| predicate(tx))
+    {
+        tracker.apply(tx)?;
+    }
+
+    info!("Filtered balance for wallet {}: {}", wallet, tracker.minor_units());
+    Ok(tracker.minor_units())
+}
+
+/// Measures how long a wallet has been inactive
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to check
+/// * `now` - Time to measure inactivity up to
+///
+/// # Returns
+/// * `Option<Duration>` - Time since the wallet's most recent transaction (zero if that
+///   transaction is timestamped after `now`), or None if the wallet has no transactions
+pub fn time_since_last_activity(
+    transactions: &[Transaction],
+    wallet: &str,
+    now: DateTime<Local>,
+) -> Option<Duration> {
+    let last = transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet))
+        .map(|tx| tx.timestamp)
+        .max()?;
+    Some((now - last).max(Duration::zero()))
+}
+
+/// Appends a batch of transactions only if every affected wallet stays valid
+///
+/// The combined history is validated for each wallet the batch touches before
+/// anything is committed, so a single overdraft or invalid amount rejects the
+/// whole batch and leaves `existing` untouched.
+///
+/// # Arguments
+/// * `existing` - Committed transaction history to extend
+/// * `batch` - Transactions to append, in order
+///
+/// # Returns
+/// * `Result<(), WalletError>` - Success, or the first validation error found
+pub fn apply_batch(existing: &mut Vec<Transaction>, batch: Vec<Transaction>) -> Result<(), WalletError> {
+    let mut combined = existing.clone();
+    combined.extend(batch.iter().cloned());
+
+    for wallet in wallet_addresses(&batch) {
+        if let Err(e) = calculate_wallet_balance(&combined, wallet) {
+            error!("Rejected batch of {} transactions: wallet {} invalid: {}", batch.len(), wallet, e);
+            return Err(e);
+        }
+    }
+
+    info!("Applied batch of {} transactions", batch.len());
+    *existing = combined;
+    Ok(())
+}
+
+/// Calculates a wallet's balance as if one transaction were moved to another position
+///
+/// The input is left untouched; the reordered history is fully revalidated, so
+/// funds checks apply in the new order. A `new_index` past the end moves the
+/// transaction to the end.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balance for
+/// * `id` - Identifier of the transaction to move
+/// * `new_index` - Position of the transaction in the reordered history
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Resulting balance, `TransactionNotFound` for an
+///   unknown identifier, or the validation error of the reordered history
+pub fn balance_with_reorder(
+    transactions: &[Transaction],
+    wallet: &str,
+    id: u64,
+    new_index: usize,
+) -> Result<i64, WalletError> {
+    let Some(index) = transactions.iter().position(|tx| tx.id == id) else {
+        error!("Cannot reorder unknown transaction {}", id);
+        return Err(WalletError::TransactionNotFound(id));
+    };
+
+    let mut reordered: Vec<&Transaction> = transactions.iter().collect();
+    let moved = reordered.remove(index);
+    reordered.insert(new_index.min(reordered.len()), moved);
+
+    let mut tracker = BalanceTracker::new(wallet);
+    for tx in reordered.into_iter().filter(|tx| tx.involves(wallet)) {
+        tracker.apply(tx)?;
+    }
+    info!(
+        "Balance for wallet {} with transaction {} moved to index {}: {}",
+        wallet, id, new_index, tracker.minor_units()
+    );
+    Ok(tracker.minor_units())
+}
+
+/// Calculates the per-period interest rate implied by two balance observations
+///
+/// This is the compounding rate that would have grown `start_balance` into
+/// `end_balance`, the same calculation as `required_rate_bps` applied to history.
+///
+/// # Arguments
+/// * `start_balance` - Balance at the first observation
+/// * `end_balance` - Balance at the second observation
+/// * `periods` - Number of compounding periods between the observations
+///
+/// # Returns
+/// * `Option<u32>` - Rate in basis points per period, rounded to the nearest basis point,
+///   or None if the start balance is not positive, the balance shrank, or there are no periods
+pub fn implied_rate_bps(start_balance: i64, end_balance: i64, periods: u32) -> Option<u32> {
+    required_rate_bps(start_balance, end_balance, periods)
+}
+
+/// Checks that every wallet's history replays without validation errors
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to validate
+///
+/// # Returns
+/// * `Result<(), WalletError>` - Success, or the first error in wallet address order
+pub fn validate_history(transactions: &[Transaction]) -> Result<(), WalletError> {
+    for wallet in wallet_addresses(transactions) {
+        calculate_wallet_balance(transactions, wallet)?;
+    }
+    Ok(())
+}
+
+/// Number of wallets in a generated sample history
+const SAMPLE_WALLETS: usize = 3;
+
+/// Number of transactions in a generated sample history
+const SAMPLE_LENGTH: usize = 24;
+
+/// Generates a reproducible, valid transaction history for tests and demos
+///
+/// The same seed always produces the same wallets, types, amounts, and
+/// timestamps; transaction identifiers are freshly assigned on each call.
+/// Withdrawals and transfers never exceed the sender's balance.
+///
+/// # Arguments
+/// * `seed` - Seed for the pseudo-random generator
+///
+/// # Returns
+/// * `Vec<Transaction>` - Generated history across wallets `sample_1` to `sample_3`
+pub fn sample_history(seed: u64) -> Vec<Transaction> {
+    // xorshift64* needs a non-zero state
+    let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+    if state == 0 {
+        state = 1;
+    }
+    let mut next = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    };
+
+    // 2024-01-01 00:00:00 UTC, a fixed starting point for reproducible timestamps
+    let start = DateTime::from_timestamp(1_704_067_200, 0)
+        .expect("valid fixed timestamp")
+        .with_timezone(&Local);
+    let wallet = |index: usize| format!("sample_{}", index + 1);
+    let mut balances = [0i64; SAMPLE_WALLETS];
+    let mut history = Vec::with_capacity(SAMPLE_LENGTH);
+
+    for step in 0..SAMPLE_LENGTH {
+        let source = (next() % SAMPLE_WALLETS as u64) as usize;
+        let roll = next();
+        let (transaction_type, amount) = if balances[source] == 0 || roll % 3 == 0 {
+            (TransactionType::Deposit, 10 + (next() % 491) as i64)
+        } else {
+            let amount = 1 + (next() % balances[source] as u64) as i64;
+            if roll % 3 == 1 {
+                (TransactionType::Withdrawal, amount)
+            } else {
+                let offset = 1 + (next() % (SAMPLE_WALLETS as u64 - 1)) as usize;
+                let destination = (source + offset) % SAMPLE_WALLETS;
+                balances[destination] += amount;
+                (TransactionType::Transfer { to: wallet(destination) }, amount)
+            }
+        };
+        match transaction_type {
+            TransactionType::Deposit => balances[source] += amount,
+            _ => balances[source] -= amount,
+        }
+
+        history.push(Transaction {
+            timestamp: start + Duration::hours(step as i64),
+            ..Transaction::new(transaction_type, wallet(source), amount)
+        });
+    }
+
+    info!("Generated sample history of {} transactions from seed {}", history.len(), seed);
+    history
+}
+
+/// Reports a wallet's balance at the start of a window and at the end of the history
+///
+/// The whole history is validated, so transactions before the window still
+/// have to be valid and still fund withdrawals inside it.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to calculate balances for
+/// * `start_index` - Position in `transactions` where the window begins
+///
+/// # Returns
+/// * `Result<(i64, i64), WalletError>` - Opening balance before `start_index` and final
+///   balance, or error if validation fails
+pub fn balance_window(
+    transactions: &[Transaction],
+    wallet: &str,
+    start_index: usize,
+) -> Result<(i64, i64), WalletError> {
+    let balances = running_balances(transactions, wallet)?;
+    let opening = balances
+        .iter()
+        .take_while(|(index, _)| *index < start_index)
+        .last()
+        .map_or(0, |(_, balance)| *balance);
+    let closing = balances.last().map_or(0, |(_, balance)| *balance);
+
+    info!(
+        "Balance window for wallet {} from index {}: opened at {}, closed at {}",
+        wallet, start_index, opening, closing
+    );
+    Ok((opening, closing))
+}
+
+/// Removes zero-amount transactions, which never affect any balance
+///
+/// # Arguments
+/// * `transactions` - Transaction history to clean in place
+///
+/// # Returns
+/// * `usize` - Number of transactions removed
+pub fn remove_zero_amounts(transactions: &mut Vec<Transaction>) -> usize {
+    let before = transactions.len();
+    transactions.retain(|tx| tx.amount != Money::ZERO);
+    let removed = before - transactions.len();
+    info!("Removed {} zero-amount transactions", removed);
+    removed
+}
+
+/// Finds the highest balance a wallet ever reached
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet to inspect
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Peak running balance (0 for a wallet with no
+///   transactions), or error if validation fails
+pub fn maximum_balance(transactions: &[Transaction], wallet: &str) -> Result<i64, WalletError> {
+    let peak = running_balances(transactions, wallet)?
+        .into_iter()
+        .map(|(_, balance)| balance)
+        .fold(0, i64::max);
+    info!("High-water mark for wallet {}: {}", wallet, peak);
+    Ok(peak)
+}
+
+/// Breaks a wallet's net flow down by transaction type
+///
+/// Each entry is the signed balance effect of that type, so deposits are
+/// positive, withdrawals negative, and transfers net their inbound and
+/// outbound legs.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet` - Address of the wallet to break down
+///
+/// # Returns
+/// * `Result<HashMap<String, i64>, WalletError>` - Net flow keyed by transaction type
+///   name, or error if the history is invalid
+pub fn flow_breakdown(transactions: &[Transaction], wallet: &str) -> Result<HashMap<String, i64>, WalletError> {
+    calculate_wallet_balance(transactions, wallet)?;
+
+    let mut flows = HashMap::new();
+    for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+        *flows.entry(tx.transaction_type.to_string()).or_insert(0) += tx.balance_delta(wallet);
+    }
+    info!("Flow breakdown for wallet {}: {:?}", wallet, flows);
+    Ok(flows)
+}
+
+/// Finds the first and last times a wallet was active
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet to inspect
+///
+/// # Returns
+/// * `Option<(DateTime<Local>, DateTime<Local>)>` - Earliest and latest timestamps of the
+///   wallet's transactions, or None if it has none
+pub fn activity_span(
+    transactions: &[Transaction],
+    wallet: &str,
+) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet))
+        .map(|tx| tx.timestamp)
+        .fold(None, |span, timestamp| match span {
+            None => Some((timestamp, timestamp)),
+            Some((first, last)) => Some((first.min(timestamp), last.max(timestamp))),
+        })
+}
+
+/// Smooths a wallet's running balance with a moving average
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet to smooth
+/// * `window` - Number of consecutive running balances averaged together
+///
+/// # Returns
+/// * `Result<Vec<f64>, WalletError>` - One average per full window, in history order
+///   (empty if the wallet has fewer than `window` transactions), `InvalidWindow` for
+///   a zero window, or the validation error of the history
+pub fn rolling_average_balance(
+    transactions: &[Transaction],
+    wallet: &str,
+    window: usize,
+) -> Result<Vec<f64>, WalletError> {
+    if window == 0 {
+        error!("Rejected rolling average with a zero window");
+        return Err(WalletError::InvalidWindow(window));
+    }
+
+    let balances: Vec<i64> = running_balances(transactions, wallet)?
+        .into_iter()
+        .map(|(_, balance)| balance)
+        .collect();
+    Ok(balances
+        .windows(window)
+        .map(|slice| slice.iter().map(|&balance| balance as f64).sum::<f64>() / window as f64)
+        .collect())
+}
+
+/// Tolerance allowed when checking that scenario probabilities sum to one
+const PROBABILITY_TOLERANCE: f64 = 1e-6;
+
+/// Forecasts a balance as the probability-weighted outcome of several scenarios
+///
+/// # Arguments
+/// * `current` - Balance before any scenario plays out
+/// * `scenarios` - (probability, balance change) pairs covering every outcome
+///
+/// # Returns
+/// * `Result<f64, WalletError>` - `current` plus the expected change, or
+///   `InvalidProbabilities` if a probability is negative or they do not sum to 1.0
+pub fn expected_balance(current: i64, scenarios: &[(f64, i64)]) -> Result<f64, WalletError> {
+    let total: f64 = scenarios.iter().map(|(probability, _)| probability).sum();
+    let negative = scenarios.iter().any(|(probability, _)| *probability < 0.0);
+    if negative || !total.is_finite() || (total - 1.0).abs() > PROBABILITY_TOLERANCE {
+        error!("Rejected scenarios with probabilities summing to {}", total);
+        return Err(WalletError::InvalidProbabilities(total));
+    }
+
+    let expected_change: f64 = scenarios
+        .iter()
+        .map(|(probability, delta)| probability * *delta as f64)
+        .sum();
+    Ok(current as f64 + expected_change)
+}
+
+/// Finds where a wallet's cumulative fees first exceed a threshold
+///
+/// Fees are charged to the wallet that initiates a transaction, so transfers
+/// only count against their source wallet.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet` - Address of the wallet paying the fees
+/// * `threshold` - Fee total that must be exceeded
+///
+/// # Returns
+/// * `Option<usize>` - Index in `transactions` of the transaction that pushed the
+///   cumulative fees above `threshold`, or None if they never exceed it
+pub fn fees_threshold_index(transactions: &[Transaction], wallet: &str, threshold: i64) -> Option<usize> {
+    let mut cumulative = 0i64;
+    let index = transactions.iter().position(|tx| {
+        if tx.wallet_address == wallet {
+            cumulative = cumulative.saturating_add(tx.fee);
+        }
+        cumulative > threshold
+    });
+    if let Some(index) = index {
+        info!("Fees for wallet {} exceeded {} at index {}", wallet, threshold, index);
+    }
+    index
+}
+
+/// Renders a history as a stable, sorted, line-per-transaction string
+///
+/// Each line holds every field except the identifier, so histories with the
+/// same transactions canonicalize identically whatever their order or ids.
+/// Text fields are quoted and escaped so a memo cannot break the line format.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to render
+///
+/// # Returns
+/// * `String` - Sorted lines, each terminated by a newline
+pub fn canonicalize(transactions: &[Transaction]) -> String {
+    let mut lines: Vec<String> = transactions
+        .iter()
+        .map(|tx| {
+            let destination = match &tx.transaction_type {
+                TransactionType::Transfer { to } => format!("{:?}", to),
+                _ => String::from("-"),
+            };
+            format!(
+                "{} {} {:?} {} {} {:?} fee={} {:?} memo={:?} key={:?}",
+                tx.timestamp.to_rfc3339(),
+                tx.transaction_type,
+                tx.wallet_address,
+                destination,
+                tx.amount.minor_units(),
+                tx.currency,
+                tx.fee,
+                tx.status,
+                tx.memo,
+                tx.idempotency_key
+            )
+        })
+        .collect();
+    lines.sort();
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// Calculates a wallet's balance after only the first `cutoff` transactions
+///
+/// Amount and funds validation still applies to the transactions inside the
+/// window; a cutoff past the end of the history covers every transaction.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions, in order
+/// * `wallet_address` - Address of the wallet to calculate balance for
+/// * `cutoff` - Number of leading transactions to consider
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Balance at the cutoff or error if validation fails
+pub fn balance_at(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    cutoff: usize,
+) -> Result<i64, WalletError> {
+    let window = &transactions[..cutoff.min(transactions.len())];
+    info!(
+        "Calculating balance for wallet {} after the first {} transactions",
+        wallet_address,
+        window.len()
+    );
+    calculate_wallet_balance(window, wallet_address)
+}
+
+/// Measures how actively funds move through a wallet
+///
+/// Throughput is the total of the wallet's deposit and withdrawal amounts; the
+/// average balance is the mean of the running balances after each of its
+/// transactions.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet to analyze
+///
+/// # Returns
+/// * `Result<Option<f64>, WalletError>` - Throughput divided by average balance,
+///   `None` when the average balance is zero, or error if validation fails
+pub fn turnover_ratio(transactions: &[Transaction], wallet: &str) -> Result<Option<f64>, WalletError> {
+    let balances = running_balances(transactions, wallet)?;
+    if balances.is_empty() {
+        return Ok(None);
+    }
+
+    // Summed in i128: a valid history can move more than i64::MAX through a wallet
+    let throughput: i128 = transactions
+        .iter()
+        .filter(|tx| tx.wallet_address == wallet)
+        .filter(|tx| matches!(tx.transaction_type, TransactionType::Deposit | TransactionType::Withdrawal))
+        .map(|tx| tx.amount.minor_units() as i128)
+        .sum();
+    let average = balances.iter().map(|&(_, balance)| balance as f64).sum::<f64>() / balances.len() as f64;
+    if average == 0.0 {
+        info!("Wallet {} has a zero average balance; turnover undefined", wallet);
+        return Ok(None);
+    }
+
+    let ratio = throughput as f64 / average;
+    info!("Turnover ratio for wallet {}: {:.4}", wallet, ratio);
+    Ok(Some(ratio))
+}
+
+/// Verifies that a batch of transactions conserves the total money held
+///
+/// The wallets in `before` form the system being checked. Each is replayed
+/// from its opening balance, and the total afterwards must equal the opening
+/// total plus deposits minus withdrawals and fees, so transfers between those
+/// wallets net to zero. Money moved to or from a wallet outside `before` breaks
+/// conservation; new wallets should be listed with a zero balance.
+///
+/// # Arguments
+/// * `before` - Opening balance of every wallet in the system
+/// * `transactions` - Transactions applied on top of the opening balances
+///
+/// # Returns
+/// * `Result<(), WalletError>` - Success, `ConservationViolation` on a mismatch,
+///   or the validation error of a wallet's replay
+pub fn verify_conservation(
+    before: &HashMap<String, i64>,
+    transactions: &[Transaction],
+) -> Result<(), WalletError> {
+    let opening: i64 = before.values().fold(0, |total, &balance| total.saturating_add(balance));
+    let expected = transactions.iter().fold(opening, |total, tx| {
+        let net = match tx.transaction_type {
+            TransactionType::Deposit => total.saturating_add(tx.amount.minor_units()),
+            TransactionType::Withdrawal => total.saturating_sub(tx.amount.minor_units()),
+            TransactionType::Transfer { .. } => total,
+        };
+        net.saturating_sub(tx.fee)
+    });
+
+    let mut actual: i64 = 0;
+    for (wallet, &balance) in before {
+        let mut tracker = BalanceTracker::starting_at(wallet, Money::from_minor(balance));
+        for tx in transactions.iter().filter(|tx| tx.involves(wallet)) {
+            tracker.apply(tx).map_err(|source| WalletError::WalletValidation {
+                wallet: wallet.clone(),
+                source: Box::new(source),
+            })?;
+        }
+        actual = actual.saturating_add(tracker.minor_units());
+    }
+
+    if actual != expected {
+        error!(
+            "Conservation violated across {} wallets: expected {}, found {}",
+            before.len(),
+            expected,
+            actual
+        );
+        return Err(WalletError::ConservationViolation { expected, actual });
+    }
+    info!("Conservation holds across {} wallets at total {}", before.len(), actual);
+    Ok(())
+}
+
+/// Accrues simple interest on a wallet's current balance as a new deposit
+///
+/// Interest is `balance * rate_bps / 10000`, rounded down. Zero or negative
+/// balances accrue nothing, and no deposit is added when the interest is zero.
+///
+/// # Arguments
+/// * `transactions` - Transaction history to append the interest deposit to
+/// * `wallet_address` - Address of the wallet earning interest
+/// * `rate_bps` - Interest rate in basis points (100 bps = 1%)
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Interest added, `BalanceOverflow` if the interest or
+///   the resulting balance does not fit in an i64, or the history's validation error
+pub fn apply_interest(
+    transactions: &mut Vec<Transaction>,
+    wallet_address: &str,
+    rate_bps: u32,
+) -> Result<i64, WalletError> {
+    let balance = calculate_wallet_balance(transactions, wallet_address)?;
+    if balance <= 0 {
+        info!("Wallet {} has no positive balance to accrue interest on", wallet_address);
+        return Ok(0);
+    }
+
+    let overflow = || {
+        error!("Interest overflow in wallet {} at balance {}", wallet_address, balance);
+        WalletError::BalanceOverflow {
+            wallet: wallet_address.to_string(),
+        }
+    };
+    let interest = i64::try_from(i128::from(balance) * i128::from(rate_bps) / 10_000).map_err(|_| overflow())?;
+    if interest == 0 {
+        return Ok(0);
+    }
+    balance.checked_add(interest).ok_or_else(overflow)?;
+
+    transactions.push(Transaction {
+        memo: Some(String::from("interest")),
+        ..Transaction::new(TransactionType::Deposit, wallet_address, interest)
+    });
+    info!("Accrued interest of {} on wallet {} at {} bps", interest, wallet_address, rate_bps);
+    Ok(interest)
+}
+
+/// Lists the funds lots a wallet still holds after withdrawals consume the oldest first
+///
+/// Each incoming transaction (a deposit or a received transfer) opens a lot;
+/// withdrawals, outgoing transfers, and fees drain lots in FIFO order. Fully
+/// consumed lots are omitted.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay, in order
+/// * `wallet` - Address of the wallet to report lots for
+///
+/// # Returns
+/// * `Result<Vec<(u64, i64)>, WalletError>` - Transaction id and unconsumed amount of
+///   each remaining lot, oldest first, or error if validation fails
+pub fn remaining_lots(transactions: &[Transaction], wallet: &str) -> Result<Vec<(u64, i64)>, WalletError> {
+    let lots: Vec<(u64, i64)> = fifo_lots(transactions, wallet)?
+        .into_iter()
+        .map(|(tx, remaining)| (tx.id, remaining))
+        .collect();
+    info!("Wallet {} holds {} remaining lots", wallet, lots.len());
+    Ok(lots)
+}
+
+/// Previews a wallet's balance with its most recent `n` transactions removed
+///
+/// Only transactions involving the wallet are counted, so other wallets'
+/// activity never shifts the cutoff.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions, in order
+/// * `wallet` - Address of the wallet to preview
+/// * `n` - Number of the wallet's latest transactions to leave out
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Balance before those transactions (0 when `n`
+///   covers the whole history), or error if validation fails
+pub fn balance_without_last_n(transactions: &[Transaction], wallet: &str, n: usize) -> Result<i64, WalletError> {
+    let history: Vec<Transaction> = transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet))
+        .cloned()
+        .collect();
+    let kept = history.len().saturating_sub(n);
+    info!(
+        "Previewing wallet {} without its last {} of {} transactions",
+        wallet,
+        history.len() - kept,
+        history.len()
+    );
+    calculate_wallet_balance(&history[..kept], wallet)
+}
+
+/// Lists every wallet address appearing in a set of transactions
+///
+/// Transfer destinations are included, and the addresses are deduplicated and
+/// sorted so the listing is deterministic.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+///
+/// # Returns
+/// * `Vec<String>` - Unique wallet addresses in ascending order
+pub fn list_wallets(transactions: &[Transaction]) -> Vec<String> {
+    wallet_addresses(transactions).into_iter().map(str::to_string).collect()
+}
+
+/// Block characters used by `balance_sparkline`, from lowest to highest
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders a wallet's running balance as a compact sparkline
+///
+/// Each transaction involving the wallet contributes one block, scaled between
+/// the lowest and highest running balance. A flat balance renders every block
+/// at the lowest level.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to replay
+/// * `wallet` - Address of the wallet to chart
+///
+/// # Returns
+/// * `Result<String, WalletError>` - One block character per wallet transaction, or the
+///   validation error of the wallet's replay
+pub fn balance_sparkline(transactions: &[Transaction], wallet: &str) -> Result<String, WalletError> {
+    let balances: Vec<i64> = running_balances(transactions, wallet)?
+        .into_iter()
+        .map(|(_, balance)| balance)
+        .collect();
+    let (Some(&min), Some(&max)) = (balances.iter().min(), balances.iter().max()) else {
+        return Ok(String::new());
+    };
+
+    let top = (SPARK_BLOCKS.len() - 1) as i128;
+    let range = max as i128 - min as i128;
+    let sparkline: String = balances
+        .iter()
+        .map(|&balance| {
+            let level = if range == 0 { 0 } else { (balance as i128 - min as i128) * top / range };
+            SPARK_BLOCKS[level as usize]
+        })
+        .collect();
+    info!("Balance sparkline for wallet {}: {}", wallet, sparkline);
+    Ok(sparkline)
+}
+
+/// Totals the amounts of a wallet's transactions of a single type
+///
+/// Transfers match only when their destination matches too, and count for
+/// both the sending and the receiving wallet. Fees are not included.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to scan
+/// * `wallet_address` - Address of the wallet to total
+/// * `tx_type` - Transaction type to include
+///
+/// # Returns
+/// * `Result<i64, WalletError>` - Sum of the matching amounts in minor units, `InvalidAmount`
+///   for a negative amount, or `BalanceOverflow` if the sum does not fit
+pub fn sum_by_type(
+    transactions: &[Transaction],
+    wallet_address: &str,
+    tx_type: &TransactionType,
+) -> Result<i64, WalletError> {
+    let mut total = Money::ZERO;
+    for tx in transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet_address) && tx.transaction_type == *tx_type)
+    {
+        if tx.amount.is_negative() {
+            error!("Invalid transaction amount: {} in transaction {:?}", tx.amount, tx);
+            return Err(WalletError::InvalidAmount(tx.amount.minor_units()));
+        }
+        total = total.checked_add(tx.amount).ok_or_else(|| WalletError::BalanceOverflow {
+            wallet: wallet_address.to_string(),
+        })?;
+    }
+    info!("Total {} amount for wallet {}: {}", tx_type, wallet_address, total);
+    Ok(total.minor_units())
+}
+
+/// Positions of each wallet's transactions within a transaction log
+///
+/// Lets repeated balance lookups replay only the transactions involving a
+/// wallet instead of filtering the whole log. The index must be kept in step
+/// with the log it describes: record every pushed transaction, and rebuild it
+/// after any other change.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalletIndex {
+    positions: HashMap<String, Vec<usize>>,
+}
+
+impl WalletIndex {
+    /// Creates an empty index for an empty log
+    ///
+    /// # Returns
+    /// * `Self` - Index with no wallets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes an existing transaction log
+    ///
+    /// # Arguments
+    /// * `transactions` - Log to index, in order
+    ///
+    /// # Returns
+    /// * `Self` - Index covering every transaction in the log
+    pub fn build(transactions: &[Transaction]) -> Self {
+        let mut index = Self::new();
+        for (position, tx) in transactions.iter().enumerate() {
+            index.record(position, tx);
+        }
+        index
+    }
+
+    /// Records a transaction pushed onto the log
+    ///
+    /// Transfers are indexed under both the source and the destination wallet.
+    ///
+    /// # Arguments
+    /// * `position` - Index of the transaction in the log
+    /// * `tx` - The pushed transaction
+    pub fn record(&mut self, position: usize, tx: &Transaction) {
+        for wallet in Self::affected(tx) {
+            self.positions.entry(wallet.to_string()).or_default().push(position);
+        }
+    }
+
+    /// Forgets the last transaction of the log after it has been popped
+    ///
+    /// # Arguments
+    /// * `position` - Index the popped transaction had in the log
+    /// * `tx` - The popped transaction
+    pub fn forget(&mut self, position: usize, tx: &Transaction) {
+        for wallet in Self::affected(tx) {
+            if let Some(positions) = self.positions.get_mut(wallet) {
+                if positions.last() == Some(&position) {
+                    positions.pop();
+                }
+                if positions.is_empty() {
+                    self.positions.remove(wallet);
+                }
+            }
+        }
+    }
+
+    /// Returns the log positions of a wallet's transactions, in order
+    ///
+    /// # Arguments
+    /// * `wallet` - Address of the wallet to look up
+    ///
+    /// # Returns
+    /// * `&[usize]` - Positions of the transactions involving the wallet, empty if none
+    pub fn positions(&self, wallet: &str) -> &[usize] {
+        self.positions.get(wallet).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Looks up a wallet's transactions in the log this index describes
+    ///
+    /// # Arguments
+    /// * `transactions` - The log this index describes
+    /// * `wallet` - Address of the wallet to look up
+    ///
+    /// # Returns
+    /// * `Result<Vec<&Transaction>, WalletError>` - The wallet's transactions in order, or
+    ///   `StaleIndex` if a position is out of range or holds another wallet's transaction
+    pub fn transactions<'a>(&self, transactions: &'a [Transaction], wallet: &str) -> Result<Vec<&'a Transaction>, WalletError> {
+        self.positions(wallet)
+            .iter()
+            .map(|&position| {
+                transactions
+                    .get(position)
+                    .filter(|tx| Self::affected(tx).contains(&wallet))
+                    .ok_or_else(|| {
+                        error!("Index for wallet {} is stale at position {}", wallet, position);
+                        WalletError::StaleIndex { wallet: wallet.to_string(), position }
+                    })
+            })
+            .collect()
+    }
+
+    /// Calculates a wallet's balance by replaying only its indexed transactions
+    ///
+    /// # Arguments
+    /// * `transactions` - The log this index describes
+    /// * `wallet` - Address of the wallet to calculate balance for
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - The same result `calculate_wallet_balance` gives
+    ///   for the full log, or `StaleIndex` if the index no longer matches it
+    pub fn balance(&self, transactions: &[Transaction], wallet: &str) -> Result<i64, WalletError> {
+        let mut tracker = BalanceTracker::new(wallet);
+        for tx in self.transactions(transactions, wallet)? {
+            tracker.apply(tx)?;
+        }
+        info!("Final indexed balance for wallet {}: {}", wallet, tracker.minor_units());
+        Ok(tracker.minor_units())
+    }
+
+    /// Wallets whose history a transaction belongs to, without duplicates
+    fn affected(tx: &Transaction) -> Vec<&str> {
+        let mut affected = vec![tx.wallet_address.as_str()];
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            if to != &tx.wallet_address {
+                affected.push(to.as_str());
+            }
+        }
+        affected
+    }
+}
+
+/// Pairs each of a wallet's transactions with the running balance after it
+///
+/// Balances are accumulated without validation, so an invalid history still
+/// produces a listing that shows where it went wrong.
+///
+/// # Arguments
+/// * `transactions` - Slice of transactions to process
+/// * `wallet_address` - Address of the wallet to list history for
+///
+/// # Returns
+/// * `Vec<(&Transaction, i64)>` - The wallet's transactions in order with running balances
+pub fn transaction_history_with_balances<'a>(
+    transactions: &'a [Transaction],
+    wallet_address: &str,
+) -> Vec<(&'a Transaction, i64)> {
+    let mut balance = 0i64;
+    transactions
+        .iter()
+        .filter(|tx| tx.involves(wallet_address))
+        .map(|tx| {
+            balance = balance.saturating_add(tx.balance_delta(wallet_address));
+            (tx, balance)
+        })
+        .collect()
+}
+
+/// Displays transaction history for a specific wallet
+/// 
+/// # Arguments
+/// * `transactions` - Slice of transactions to display
+/// * `wallet_address` - Address of the wallet to show history for
+pub fn print_transaction_history(transactions: &[Transaction], wallet_address: &str) {
+    if let Err(e) = export::write_transaction_history(
+        transactions,
+        wallet_address,
+        export::HistoryFormat::Plain,
+        std::io::stdout(),
+    ) {
+        error!("Failed to print history for wallet {}: {}", wallet_address, e);
     }
 } 
\ No newline at end of file
@@ -1,13 +1,24 @@
 //! Entry point for the Ryz Labs Wallet Balance Tracker
-//! Initializes and runs the interactive terminal interface
+//! Initializes and runs the interactive terminal interface, or a command
+//! script when input is piped in
 
 use ryz_labs::terminal::WalletTerminal;
+use std::io::{self, IsTerminal};
 
 /// Main function - initializes and runs the wallet terminal
 fn main() {
     // Create new terminal instance
     let mut terminal = WalletTerminal::new();
-    
+
+    // Piped input is treated as a script, e.g. `cargo run < script.txt`
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        if let Err(e) = terminal.run_script_session(stdin.lock()) {
+            eprintln!("Error reading script: {}", e);
+        }
+        return;
+    }
+
     // Start the interactive terminal session
     terminal.run();
 }
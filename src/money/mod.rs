@@ -0,0 +1,290 @@
+//! Amount handling module for the Ryz Labs Wallet Balance Tracker
+//! Provides the fixed-point money type plus locale-aware parsing, display
+//! formatting, and breakdown of amounts
+
+use crate::WalletError;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of minor units (cents) in one major unit
+pub const MINOR_UNITS_PER_MAJOR: i64 = 100;
+
+/// An amount of money stored as a whole number of minor units (cents)
+///
+/// Serializes as the bare minor-unit integer, so `Money::from_minor(1050)`
+/// is written as `1050` and displayed as `10.50`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    /// No money at all
+    pub const ZERO: Money = Money(0);
+
+    /// Creates an amount from minor units, e.g. `from_minor(1050)` is 10.50
+    pub const fn from_minor(minor: i64) -> Self {
+        Money(minor)
+    }
+
+    /// Creates an amount from whole major units, e.g. `from_major(10)` is 10.00
+    ///
+    /// # Returns
+    /// * `Option<Money>` - The amount, or None if it does not fit in minor units
+    pub fn from_major(major: i64) -> Option<Self> {
+        major.checked_mul(MINOR_UNITS_PER_MAJOR).map(Money)
+    }
+
+    /// Returns the amount as a whole number of minor units
+    pub const fn minor_units(self) -> i64 {
+        self.0
+    }
+
+    /// Checks whether the amount is below zero
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Adds two amounts, returning None instead of wrapping on overflow
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    /// Subtracts an amount, returning None instead of wrapping on overflow
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+}
+
+/// Plain integers are taken as minor units
+impl From<i64> for Money {
+    fn from(minor: i64) -> Self {
+        Money(minor)
+    }
+}
+
+// Render amounts in major units with two decimal places, e.g. "10.50" or "-0.05"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let magnitude = self.0.unsigned_abs();
+        let per_major = MINOR_UNITS_PER_MAJOR as u64;
+        write!(f, "{}{}.{:02}", sign, magnitude / per_major, magnitude % per_major)
+    }
+}
+
+/// Separator conventions used when reading numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    /// Character grouping thousands in the integer part (e.g. ',' in "1,234")
+    pub thousands_separator: char,
+    /// Character separating the integer and fractional parts (e.g. '.' in "1.5")
+    pub decimal_separator: char,
+}
+
+impl NumberLocale {
+    /// US/UK style numbers such as "1,234.56"
+    pub const US: NumberLocale = NumberLocale {
+        thousands_separator: ',',
+        decimal_separator: '.',
+    };
+
+    /// Continental European style numbers such as "1.234,56"
+    pub const EUROPEAN: NumberLocale = NumberLocale {
+        thousands_separator: '.',
+        decimal_separator: ',',
+    };
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        NumberLocale::US
+    }
+}
+
+/// Parses a user-entered amount according to a number locale
+///
+/// Separators are interpreted strictly: thousands separators must split the
+/// integer part into groups of three digits, at most one decimal separator is
+/// allowed, and any other character is rejected. Ambiguous inputs such as
+/// "1.234" therefore mean 1234 under European grouping but 1.234 under US rules.
+///
+/// # Arguments
+/// * `input` - Text entered by the user
+/// * `locale` - Separator conventions to apply
+///
+/// # Returns
+/// * `Result<f64, WalletError>` - Parsed value or `UnparseableAmount` for malformed input
+pub fn parse_amount(input: &str, locale: &NumberLocale) -> Result<f64, WalletError> {
+    let trimmed = input.trim();
+    let (negative, integer, fraction) = split_amount(trimmed, locale)?;
+
+    let mut normalized = format!("{}{}", if negative { "-" } else { "" }, integer);
+    if let Some(fraction) = fraction {
+        normalized.push('.');
+        normalized.push_str(&fraction);
+    }
+
+    let value = normalized
+        .parse::<f64>()
+        .map_err(|_| unparseable(trimmed, locale))?;
+    debug!("Parsed amount '{}' as {} using {:?}", trimmed, value, locale);
+    Ok(value)
+}
+
+/// Parses a user-entered amount into exact minor units
+///
+/// Applies the same separator rules as `parse_amount`, but without going
+/// through floating point, so "10.50" is exactly 1050 minor units. Inputs with
+/// more decimal places than a minor unit can hold are rejected.
+///
+/// # Arguments
+/// * `input` - Text entered by the user, in major units
+/// * `locale` - Separator conventions to apply
+///
+/// # Returns
+/// * `Result<Money, WalletError>` - Parsed amount or `UnparseableAmount` for malformed,
+///   overly precise, or out-of-range input
+pub fn parse_money(input: &str, locale: &NumberLocale) -> Result<Money, WalletError> {
+    let trimmed = input.trim();
+    let (negative, integer, fraction) = split_amount(trimmed, locale)?;
+
+    let fraction = fraction.unwrap_or_default();
+    if fraction.len() > 2 {
+        return Err(unparseable(trimmed, locale));
+    }
+    let cents = format!("{:0<2}", fraction)
+        .parse::<i64>()
+        .map_err(|_| unparseable(trimmed, locale))?;
+    let minor = integer
+        .parse::<i64>()
+        .ok()
+        .and_then(|major| major.checked_mul(MINOR_UNITS_PER_MAJOR))
+        .and_then(|minor| minor.checked_add(cents))
+        .map(|minor| if negative { -minor } else { minor })
+        .ok_or_else(|| unparseable(trimmed, locale))?;
+
+    let amount = Money::from_minor(minor);
+    debug!("Parsed amount '{}' as {} using {:?}", trimmed, amount, locale);
+    Ok(amount)
+}
+
+/// Builds the error for amount text that does not follow the locale's rules
+fn unparseable(trimmed: &str, locale: &NumberLocale) -> WalletError {
+    error!("Unparseable amount for locale {:?}: {}", locale, trimmed);
+    WalletError::UnparseableAmount(trimmed.to_string())
+}
+
+/// Validates amount text and splits it into sign, integer digits, and fraction digits
+///
+/// Thousands separators are checked and removed from the integer part.
+fn split_amount(
+    trimmed: &str,
+    locale: &NumberLocale,
+) -> Result<(bool, String, Option<String>), WalletError> {
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let mut parts = unsigned.split(locale.decimal_separator);
+    let integer_part = parts.next().unwrap_or("");
+    let fraction_part = parts.next();
+    if parts.next().is_some() {
+        return Err(unparseable(trimmed, locale));
+    }
+
+    // Group separators must leave a 1-3 digit lead followed by 3-digit groups
+    let groups: Vec<&str> = integer_part.split(locale.thousands_separator).collect();
+    let well_grouped = groups.iter().enumerate().all(|(position, group)| {
+        let digits_only = group.chars().all(|c| c.is_ascii_digit());
+        let width_ok = match (position, groups.len()) {
+            (0, 1) => !group.is_empty(),
+            (0, _) => (1..=3).contains(&group.len()),
+            _ => group.len() == 3,
+        };
+        digits_only && width_ok
+    });
+    if !well_grouped {
+        return Err(unparseable(trimmed, locale));
+    }
+
+    if let Some(fraction) = fraction_part {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(unparseable(trimmed, locale));
+        }
+    }
+
+    Ok((negative, groups.concat(), fraction_part.map(str::to_string)))
+}
+
+/// Formats a balance using accounting conventions
+///
+/// Negative balances are shown in parentheses rather than with a leading minus.
+///
+/// # Arguments
+/// * `balance` - Balance to format
+///
+/// # Returns
+/// * `String` - Formatted balance, e.g. "100" or "(50)"
+pub fn format_accounting(balance: i64) -> String {
+    format_accounting_with_symbol(balance, None)
+}
+
+/// Formats a balance using accounting conventions with an optional currency symbol
+///
+/// The symbol sits inside the parentheses for negative balances, e.g. "($50)".
+///
+/// # Arguments
+/// * `balance` - Balance to format
+/// * `symbol` - Currency symbol to prefix, if any
+///
+/// # Returns
+/// * `String` - Formatted balance
+pub fn format_accounting_with_symbol(balance: i64, symbol: Option<&str>) -> String {
+    let symbol = symbol.unwrap_or("");
+    if balance < 0 {
+        format!("({}{})", symbol, balance.unsigned_abs())
+    } else {
+        format!("{}{}", symbol, balance)
+    }
+}
+
+/// Breaks an amount into denominations, largest first
+///
+/// Denominations are used greedily from the largest down, which is exact for
+/// standard currency sets; non-positive denominations are ignored.
+///
+/// # Arguments
+/// * `amount` - Amount to break down
+/// * `denoms` - Available denominations, in any order
+///
+/// # Returns
+/// * `Option<Vec<(i64, usize)>>` - Each used denomination with its count, largest first,
+///   or None if the amount is negative or cannot be made exactly
+pub fn denominations(amount: i64, denoms: &[i64]) -> Option<Vec<(i64, usize)>> {
+    if amount < 0 {
+        return None;
+    }
+
+    let mut available: Vec<i64> = denoms.iter().copied().filter(|&d| d > 0).collect();
+    available.sort_unstable_by(|a, b| b.cmp(a));
+    available.dedup();
+
+    let mut remaining = amount;
+    let mut breakdown = Vec::new();
+    for denom in available {
+        let count = remaining / denom;
+        if count > 0 {
+            breakdown.push((denom, count as usize));
+            remaining -= count * denom;
+        }
+    }
+
+    if remaining == 0 {
+        Some(breakdown)
+    } else {
+        debug!("Cannot make {} exactly from {:?}", amount, denoms);
+        None
+    }
+}
@@ -0,0 +1,97 @@
+//! Shared wallet state module for the Ryz Labs Wallet Balance Tracker
+//! Provides a transaction log that several threads can update safely
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use crate::money::Money;
+use crate::{calculate_wallet_balance, Transaction, TransactionType, WalletError};
+use log::{error, info};
+
+/// Transaction log shared between threads
+///
+/// Cloning a `SharedWallet` yields another handle to the same log. Every
+/// operation holds the lock for its whole check-and-append, so two threads
+/// cannot both pass a balance check before either records its withdrawal.
+#[derive(Debug, Clone, Default)]
+pub struct SharedWallet {
+    transactions: Arc<Mutex<Vec<Transaction>>>,
+}
+
+impl SharedWallet {
+    /// Creates an empty shared transaction log
+    ///
+    /// # Returns
+    /// * `Self` - Handle to a new, empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a deposit into a wallet
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet receiving the funds
+    /// * `amount` - Amount to deposit (plain integers are minor units)
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - The wallet's new balance in minor units, or the
+    ///   validation error that rejected the deposit
+    pub fn deposit(&self, wallet_address: &str, amount: impl Into<Money>) -> Result<i64, WalletError> {
+        self.record(Transaction::new(TransactionType::Deposit, wallet_address, amount))
+    }
+
+    /// Records a withdrawal from a wallet if it has sufficient funds
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet paying out
+    /// * `amount` - Amount to withdraw (plain integers are minor units)
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - The wallet's new balance in minor units, or the
+    ///   validation error that rejected the withdrawal
+    pub fn withdraw(&self, wallet_address: &str, amount: impl Into<Money>) -> Result<i64, WalletError> {
+        self.record(Transaction::new(TransactionType::Withdrawal, wallet_address, amount))
+    }
+
+    /// Calculates a wallet's current balance
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet to calculate balance for
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - Balance in minor units or error if validation fails
+    pub fn balance(&self, wallet_address: &str) -> Result<i64, WalletError> {
+        calculate_wallet_balance(&self.lock(), wallet_address)
+    }
+
+    /// Returns a copy of every recorded transaction, in order
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.lock().clone()
+    }
+
+    /// Validates a transaction against the log and appends it, all under one lock
+    fn record(&self, tx: Transaction) -> Result<i64, WalletError> {
+        let mut transactions = self.lock();
+        transactions.push(tx);
+        let last = transactions.len() - 1;
+        let wallet_address = transactions[last].wallet_address.clone();
+
+        match calculate_wallet_balance(&transactions, &wallet_address) {
+            Ok(balance) => {
+                info!("Recorded {} for shared wallet {}", transactions[last], wallet_address);
+                Ok(balance)
+            }
+            Err(e) => {
+                let rejected = transactions.remove(last);
+                error!("Rejected {} for shared wallet {}: {}", rejected, wallet_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Locks the log, recovering it if another thread panicked while holding the lock
+    ///
+    /// The log only ever holds validated transactions between operations, so a
+    /// poisoned lock still guards consistent data.
+    fn lock(&self) -> MutexGuard<'_, Vec<Transaction>> {
+        self.transactions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
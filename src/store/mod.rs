@@ -0,0 +1,358 @@
+//! Transaction storage module for the Ryz Labs Wallet Balance Tracker
+//! Provides interchangeable in-memory and SQLite backends for transaction logs
+
+use std::io;
+use std::path::Path;
+use crate::money::Money;
+use crate::{Transaction, TransactionStatus, TransactionType, WalletIndex};
+use chrono::{DateTime, Local};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+/// Storage backend holding a transaction log in insertion order
+pub trait TransactionStore {
+    /// Appends a transaction to the end of the log
+    ///
+    /// # Arguments
+    /// * `tx` - Transaction to store
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    fn append(&mut self, tx: Transaction) -> io::Result<()>;
+
+    /// Returns every stored transaction, in insertion order
+    fn all(&self) -> io::Result<Vec<Transaction>>;
+
+    /// Returns the transactions involving a wallet, including transfers it receives
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet to look up
+    ///
+    /// # Returns
+    /// * `io::Result<Vec<Transaction>>` - Matching transactions, in insertion order
+    fn for_wallet(&self, wallet_address: &str) -> io::Result<Vec<Transaction>> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|tx| tx.involves(wallet_address))
+            .collect())
+    }
+
+    /// Replaces the whole log, e.g. when rewinding to a checkpoint
+    ///
+    /// # Arguments
+    /// * `transactions` - New contents of the log, in order
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    fn replace_all(&mut self, transactions: Vec<Transaction>) -> io::Result<()>;
+
+    /// Removes and returns the most recently appended transaction
+    ///
+    /// # Returns
+    /// * `io::Result<Option<Transaction>>` - The removed transaction, or None if the log is empty
+    fn pop_last(&mut self) -> io::Result<Option<Transaction>> {
+        let mut transactions = self.all()?;
+        let last = transactions.pop();
+        if last.is_some() {
+            self.replace_all(transactions)?;
+        }
+        Ok(last)
+    }
+}
+
+// The in-memory backend used by default
+impl TransactionStore for Vec<Transaction> {
+    fn append(&mut self, tx: Transaction) -> io::Result<()> {
+        self.push(tx);
+        Ok(())
+    }
+
+    fn all(&self) -> io::Result<Vec<Transaction>> {
+        Ok(self.clone())
+    }
+
+    fn replace_all(&mut self, transactions: Vec<Transaction>) -> io::Result<()> {
+        *self = transactions;
+        Ok(())
+    }
+
+    fn pop_last(&mut self) -> io::Result<Option<Transaction>> {
+        Ok(self.pop())
+    }
+}
+
+/// In-memory store that indexes transactions by wallet for fast lookups
+///
+/// Wallet lookups copy only the wallet's own transactions instead of
+/// filtering the whole log.
+#[derive(Debug, Clone, Default)]
+pub struct IndexedStore {
+    transactions: Vec<Transaction>,
+    index: WalletIndex,
+}
+
+impl IndexedStore {
+    /// Creates an empty indexed store
+    ///
+    /// # Returns
+    /// * `Self` - Store ready for use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the stored transactions without copying them
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Returns the wallet index kept alongside the transactions
+    pub fn index(&self) -> &WalletIndex {
+        &self.index
+    }
+}
+
+impl TransactionStore for IndexedStore {
+    fn append(&mut self, tx: Transaction) -> io::Result<()> {
+        self.index.record(self.transactions.len(), &tx);
+        self.transactions.push(tx);
+        Ok(())
+    }
+
+    fn all(&self) -> io::Result<Vec<Transaction>> {
+        Ok(self.transactions.clone())
+    }
+
+    fn for_wallet(&self, wallet_address: &str) -> io::Result<Vec<Transaction>> {
+        let transactions = self
+            .index
+            .transactions(&self.transactions, wallet_address)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(transactions.into_iter().cloned().collect())
+    }
+
+    fn replace_all(&mut self, transactions: Vec<Transaction>) -> io::Result<()> {
+        self.index = WalletIndex::build(&transactions);
+        self.transactions = transactions;
+        Ok(())
+    }
+
+    fn pop_last(&mut self) -> io::Result<Option<Transaction>> {
+        let last = self.transactions.pop();
+        if let Some(tx) = &last {
+            self.index.forget(self.transactions.len(), tx);
+        }
+        Ok(last)
+    }
+}
+
+/// Table layout used by `SqliteStore`; `seq` preserves insertion order
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS transactions (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        id INTEGER NOT NULL,
+        transaction_type TEXT NOT NULL,
+        destination TEXT,
+        wallet_address TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        currency TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        fee INTEGER NOT NULL,
+        memo TEXT,
+        status TEXT NOT NULL,
+        idempotency_key TEXT
+    );
+    CREATE INDEX IF NOT EXISTS transactions_wallet ON transactions (wallet_address);
+    CREATE INDEX IF NOT EXISTS transactions_destination ON transactions (destination);
+";
+
+/// Columns read back into a `Transaction`, in the order `transaction_from_row` expects
+const COLUMNS: &str =
+    "id, transaction_type, destination, wallet_address, amount, currency, timestamp, fee, memo, status, idempotency_key";
+
+/// Transaction store persisting the log to a SQLite database
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens (or creates) a SQLite database file as a transaction store
+    ///
+    /// Identifiers already in the database are reserved so new transactions
+    /// cannot reuse them.
+    ///
+    /// # Arguments
+    /// * `path` - Database file to open
+    ///
+    /// # Returns
+    /// * `io::Result<Self>` - Store ready for use, or the error opening the database
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let store = Self::with_connection(Connection::open(path).map_err(storage_error)?)?;
+        info!("Opened SQLite transaction store at {}", path.display());
+        Ok(store)
+    }
+
+    /// Creates a transaction store backed by a private in-memory database
+    ///
+    /// # Returns
+    /// * `io::Result<Self>` - Empty store ready for use
+    pub fn open_in_memory() -> io::Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(storage_error)?)
+    }
+
+    /// Prepares the schema on a connection and reserves its stored identifiers
+    fn with_connection(connection: Connection) -> io::Result<Self> {
+        connection.execute_batch(SCHEMA).map_err(storage_error)?;
+        let max_id: Option<i64> = connection
+            .query_row("SELECT MAX(id) FROM transactions", [], |row| row.get(0))
+            .map_err(storage_error)?;
+        if let Some(max_id) = max_id {
+            Transaction::reserve_ids_through(max_id as u64);
+        }
+        Ok(SqliteStore { connection })
+    }
+
+    /// Runs a query returning transaction rows
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> io::Result<Vec<Transaction>> {
+        let mut statement = self.connection.prepare(sql).map_err(storage_error)?;
+        let rows = statement
+            .query_map(params, transaction_from_row)
+            .map_err(storage_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(storage_error)
+    }
+
+    /// Inserts a transaction row on the given connection or transaction
+    fn insert(connection: &Connection, tx: &Transaction) -> io::Result<()> {
+        let (transaction_type, destination) = match &tx.transaction_type {
+            TransactionType::Deposit => ("Deposit", None),
+            TransactionType::Withdrawal => ("Withdrawal", None),
+            TransactionType::Transfer { to } => ("Transfer", Some(to.as_str())),
+        };
+        let status = match tx.status {
+            TransactionStatus::Confirmed => "Confirmed",
+            TransactionStatus::Pending => "Pending",
+        };
+        let id = i64::try_from(tx.id).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("Transaction id {} too large to store", tx.id))
+        })?;
+
+        connection
+            .execute(
+                "INSERT INTO transactions
+                    (id, transaction_type, destination, wallet_address, amount, currency, timestamp, fee, memo, status,
+                     idempotency_key)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    id,
+                    transaction_type,
+                    destination,
+                    tx.wallet_address,
+                    tx.amount.minor_units(),
+                    tx.currency,
+                    tx.timestamp.to_rfc3339(),
+                    tx.fee,
+                    tx.memo,
+                    status,
+                    tx.idempotency_key,
+                ],
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+impl TransactionStore for SqliteStore {
+    fn append(&mut self, tx: Transaction) -> io::Result<()> {
+        Self::insert(&self.connection, &tx)
+    }
+
+    fn all(&self) -> io::Result<Vec<Transaction>> {
+        self.query(&format!("SELECT {} FROM transactions ORDER BY seq", COLUMNS), [])
+    }
+
+    fn for_wallet(&self, wallet_address: &str) -> io::Result<Vec<Transaction>> {
+        self.query(
+            &format!(
+                "SELECT {} FROM transactions WHERE wallet_address = ?1 OR destination = ?1 ORDER BY seq",
+                COLUMNS
+            ),
+            [wallet_address],
+        )
+    }
+
+    fn replace_all(&mut self, transactions: Vec<Transaction>) -> io::Result<()> {
+        let batch = self.connection.transaction().map_err(storage_error)?;
+        batch.execute("DELETE FROM transactions", []).map_err(storage_error)?;
+        for tx in &transactions {
+            Self::insert(&batch, tx)?;
+        }
+        batch.commit().map_err(storage_error)?;
+        info!("Replaced SQLite transaction store with {} transactions", transactions.len());
+        Ok(())
+    }
+
+    fn pop_last(&mut self) -> io::Result<Option<Transaction>> {
+        let batch = self.connection.transaction().map_err(storage_error)?;
+        let last = batch
+            .query_row(
+                &format!("SELECT {}, seq FROM transactions ORDER BY seq DESC LIMIT 1", COLUMNS),
+                [],
+                |row| Ok((transaction_from_row(row)?, row.get::<_, i64>(11)?)),
+            )
+            .optional()
+            .map_err(storage_error)?;
+        if let Some((_, seq)) = &last {
+            batch.execute("DELETE FROM transactions WHERE seq = ?1", [seq]).map_err(storage_error)?;
+        }
+        batch.commit().map_err(storage_error)?;
+        Ok(last.map(|(tx, _)| tx))
+    }
+}
+
+/// Rebuilds a transaction from a row selected with `COLUMNS`
+fn transaction_from_row(row: &Row) -> rusqlite::Result<Transaction> {
+    let invalid = |column: usize, value: String| {
+        rusqlite::Error::FromSqlConversionFailure(
+            column,
+            rusqlite::types::Type::Text,
+            format!("unexpected value '{}'", value).into(),
+        )
+    };
+
+    let kind: String = row.get(1)?;
+    let destination: Option<String> = row.get(2)?;
+    let transaction_type = match (kind.as_str(), destination) {
+        ("Deposit", _) => TransactionType::Deposit,
+        ("Withdrawal", _) => TransactionType::Withdrawal,
+        ("Transfer", Some(to)) => TransactionType::Transfer { to },
+        _ => return Err(invalid(1, kind)),
+    };
+    let timestamp: String = row.get(6)?;
+    let timestamp = DateTime::parse_from_rfc3339(&timestamp)
+        .map_err(|_| invalid(6, timestamp.clone()))?
+        .with_timezone(&Local);
+    let status: String = row.get(9)?;
+    let status = match status.as_str() {
+        "Confirmed" => TransactionStatus::Confirmed,
+        "Pending" => TransactionStatus::Pending,
+        _ => return Err(invalid(9, status)),
+    };
+
+    Ok(Transaction {
+        id: row.get::<_, i64>(0)? as u64,
+        transaction_type,
+        wallet_address: row.get(3)?,
+        amount: Money::from_minor(row.get(4)?),
+        currency: row.get(5)?,
+        timestamp,
+        fee: row.get(7)?,
+        memo: row.get(8)?,
+        status,
+        idempotency_key: row.get(10)?,
+    })
+}
+
+/// Converts a SQLite error into the I/O error used by transaction stores
+fn storage_error(error: rusqlite::Error) -> io::Error {
+    io::Error::other(error)
+}
@@ -1,17 +1,128 @@
 //! Terminal interface module for the Ryz Labs Wallet Balance Tracker
 //! Provides interactive command-line functionality for wallet operations
 
-use std::io::{self, Write};
-use crate::{Transaction, TransactionType, print_transaction_history, calculate_wallet_balance};
-use log::{info, error};
+use std::io::{self, BufRead, BufReader, Write};
+use crate::{
+    AddressRules, LOG_LEVEL_ENV, Report, Transaction, TransactionType, WalletError, build_report, print_transaction_history,
+    calculate_all_balances, calculate_wallet_balance, daily_outflow, list_wallets, log_directory, parse_log_level, shortfall, validate_daily_withdrawal_limit, validate_history,
+    wallet_summary, wallets_by_balance,
+};
+use crate::export::export_csv;
+use crate::money::{Money, NumberLocale, parse_money};
+use crate::store::{IndexedStore, TransactionStore};
+use log::{debug, info, error};
 use chrono::Local;
-use std::fs;
-use std::path::Path;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+/// Log file receiving terminal output, set once logging is successfully installed
+static ACTIVE_LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Reads the last lines of a text file
+///
+/// # Arguments
+/// * `path` - File to read
+/// * `n` - Maximum number of lines to return
+///
+/// # Returns
+/// * `io::Result<Vec<String>>` - Up to `n` final lines in file order, all lines if the
+///   file is shorter, or an empty Vec if the file does not exist
+pub fn tail_lines(path: &Path, n: usize) -> io::Result<Vec<String>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut tail = VecDeque::with_capacity(n);
+    for line in BufReader::new(file).lines() {
+        if n == 0 {
+            break;
+        }
+        if tail.len() == n {
+            tail.pop_front();
+        }
+        tail.push_back(line?);
+    }
+    Ok(tail.into())
+}
+
+/// Source of pauses used when replaying transactions
+///
+/// Abstracting the pause lets demos wait for real while tests run instantly.
+pub trait Sleeper {
+    /// Pauses for the given duration
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// Sleeper that blocks the current thread
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadSleeper;
+
+impl Sleeper for ThreadSleeper {
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// File the terminal loads its transaction log from on startup and saves it to on exit
+pub const DEFAULT_STATE_FILE: &str = "wallet_state.json";
+
+/// Number of times the terminal asks for an amount before giving up
+const MAX_AMOUNT_ATTEMPTS: usize = 3;
+
+/// Number of log lines shown by the recent activity menu option
+const RECENT_LOG_LINES: usize = 10;
+
+/// Counters describing the transactions submitted during a session
+#[derive(Debug, Clone, Copy, Default)]
+struct SessionMetrics {
+    /// Withdrawals submitted for recording, whether accepted or rejected
+    withdrawal_attempts: u64,
+    /// Withdrawals that passed validation and were recorded
+    withdrawals_recorded: u64,
+}
+
+/// Collects the idempotency keys carried by a transaction log
+fn recorded_keys(transactions: &[Transaction]) -> HashSet<String> {
+    transactions
+        .iter()
+        .filter_map(|tx| tx.idempotency_key.clone())
+        .collect()
+}
 
 /// Terminal interface for wallet operations
 pub struct WalletTerminal {
-    /// Vector storing all transactions processed in the current session
-    pub(crate) transactions: Vec<Transaction>,
+    /// Backend storing all transactions processed in the current session
+    store: Box<dyn TransactionStore>,
+    /// Named copies of the transaction log that the session can rewind to
+    checkpoints: HashMap<String, Vec<Transaction>>,
+    /// Idempotency keys of the transactions in the log, used to reject replays
+    seen_keys: HashSet<String>,
+    /// Counters for the transactions submitted in the current session
+    metrics: SessionMetrics,
+    /// Optional cap on the total a wallet may withdraw per calendar day
+    daily_withdrawal_limit: Option<i64>,
+    /// Optional ceiling on the amount of any single transaction
+    max_transaction_amount: Option<Money>,
+    /// Separator conventions used when reading amounts
+    number_locale: NumberLocale,
+    /// File the transaction log is loaded from on startup and saved to on exit, if any
+    state_path: Option<PathBuf>,
+    /// Rules wallet addresses must satisfy when entered
+    address_rules: AddressRules,
+    /// Source of interactive user input
+    input: Box<dyn BufRead>,
+}
+
+impl Default for WalletTerminal {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WalletTerminal {
@@ -25,18 +136,595 @@ impl WalletTerminal {
         }
         info!("Initializing new WalletTerminal instance");
         WalletTerminal {
-            transactions: Vec::new(),
+            store: Box::new(Vec::new()),
+            checkpoints: HashMap::new(),
+            seen_keys: HashSet::new(),
+            metrics: SessionMetrics::default(),
+            daily_withdrawal_limit: None,
+            max_transaction_amount: None,
+            number_locale: NumberLocale::default(),
+            state_path: Some(PathBuf::from(DEFAULT_STATE_FILE)),
+            address_rules: AddressRules::default(),
+            input: Box::new(BufReader::new(io::stdin())),
+        }
+    }
+
+    /// Creates a new terminal instance that reads user input from a custom source
+    ///
+    /// # Arguments
+    /// * `input` - Reader supplying the lines normally typed at the prompts
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_input(input: impl BufRead + 'static) -> Self {
+        WalletTerminal {
+            input: Box::new(input),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that keeps transactions in a custom store
+    ///
+    /// The store is responsible for its own persistence, so the terminal neither
+    /// restores nor saves a state file.
+    ///
+    /// # Arguments
+    /// * `store` - Backend holding the transaction log, e.g. a `SqliteStore`
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_store(store: impl TransactionStore + 'static) -> Self {
+        let seen_keys = match store.all() {
+            Ok(transactions) => recorded_keys(&transactions),
+            Err(e) => {
+                error!("Could not read idempotency keys from store: {}", e);
+                HashSet::new()
+            }
+        };
+        WalletTerminal {
+            store: Box::new(store),
+            seen_keys,
+            state_path: None,
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that indexes transactions by wallet
+    ///
+    /// Balance checks and validation then replay only the affected wallet's
+    /// transactions, which keeps long sessions with many wallets fast. The
+    /// transaction log is still restored from and saved to the state file.
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_wallet_index() -> Self {
+        info!("Indexing transactions by wallet");
+        WalletTerminal {
+            store: Box::new(IndexedStore::new()),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that restores from and saves to a custom state file
+    ///
+    /// Unlike `load_from_file`, the file is read when a session starts, so it
+    /// need not exist yet.
+    ///
+    /// # Arguments
+    /// * `path` - JSON file holding the transaction log between sessions
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_state_file(path: &Path) -> Self {
+        WalletTerminal {
+            state_path: Some(path.to_path_buf()),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a terminal instance from a transaction log saved with `save_to_file`
+    ///
+    /// The loaded terminal also saves back to `path` when the session exits.
+    ///
+    /// # Arguments
+    /// * `path` - JSON file to read the transaction log from
+    ///
+    /// # Returns
+    /// * `io::Result<Self>` - Terminal holding the saved transactions, or an `InvalidData`
+    ///   error if the file is not a valid transaction log
+    pub fn load_from_file(path: &Path) -> io::Result<Self> {
+        let transactions = Self::read_transactions(path)?;
+        Ok(WalletTerminal {
+            seen_keys: recorded_keys(&transactions),
+            store: Box::new(transactions),
+            state_path: Some(path.to_path_buf()),
+            ..Self::new()
+        })
+    }
+
+    /// Writes the transaction log to a JSON file
+    ///
+    /// # Arguments
+    /// * `path` - File to write, replaced if it already exists
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let transactions = self.store.all()?;
+        let mut writer = io::BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &transactions)?;
+        writer.flush()?;
+        info!("Saved {} transactions to {}", transactions.len(), path.display());
+        Ok(())
+    }
+
+    /// Reads and validates a saved transaction log
+    ///
+    /// Every wallet's history must replay without errors, so a tampered file
+    /// cannot smuggle in an overdraft.
+    fn read_transactions(path: &Path) -> io::Result<Vec<Transaction>> {
+        let file = File::open(path)?;
+        let transactions: Vec<Transaction> = serde_json::from_reader(BufReader::new(file))?;
+
+        validate_history(&transactions).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(max_id) = transactions.iter().map(|tx| tx.id).max() {
+            Transaction::reserve_ids_through(max_id);
+        }
+
+        info!("Loaded {} transactions from {}", transactions.len(), path.display());
+        Ok(transactions)
+    }
+
+    /// Restores the transaction log from the state file if one exists
+    ///
+    /// A corrupt state file is logged and ignored so the session starts fresh.
+    fn restore_state(&mut self) {
+        let Some(state_path) = self.state_path.clone().filter(|path| path.exists()) else {
+            return;
+        };
+        match Self::read_transactions(&state_path).and_then(|transactions| {
+            let count = transactions.len();
+            let keys = recorded_keys(&transactions);
+            self.store.replace_all(transactions)?;
+            self.seen_keys = keys;
+            Ok(count)
+        }) {
+            Ok(count) => {
+                println!("Restored {} transactions from {}", count, state_path.display());
+            }
+            Err(e) => {
+                error!("Ignoring unreadable state file {}: {}", state_path.display(), e);
+                println!("Could not read saved state, starting fresh: {}", e);
+                match Self::quarantine_state_file(&state_path) {
+                    Ok(moved_to) => {
+                        info!("Moved unreadable state file to {}", moved_to.display());
+                        println!("The unreadable file was moved to {}", moved_to.display());
+                    }
+                    Err(rename_error) => {
+                        // Saving over the original would destroy it, so leave it alone
+                        error!("Failed to move unreadable state file {}: {}", state_path.display(), rename_error);
+                        println!("Could not move {} aside ({}); this session will not be saved", state_path.display(), rename_error);
+                        self.state_path = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renames an unreadable state file so the next save does not overwrite it
+    ///
+    /// # Arguments
+    /// * `state_path` - The state file that failed to load
+    ///
+    /// # Returns
+    /// * `io::Result<PathBuf>` - Where the file was moved, `<name>.corrupt` or `<name>.corrupt.N` if taken
+    fn quarantine_state_file(state_path: &Path) -> io::Result<PathBuf> {
+        let base = format!("{}.corrupt", state_path.display());
+        let mut target = PathBuf::from(&base);
+        let mut suffix = 1;
+        while target.exists() {
+            target = PathBuf::from(format!("{}.{}", base, suffix));
+            suffix += 1;
+        }
+        fs::rename(state_path, &target)?;
+        Ok(target)
+    }
+
+    /// Creates a new terminal instance that reads amounts using a number locale
+    ///
+    /// # Arguments
+    /// * `locale` - Thousands and decimal separator conventions for amount input
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_number_locale(locale: NumberLocale) -> Self {
+        info!("Using number locale {:?}", locale);
+        WalletTerminal {
+            number_locale: locale,
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that enforces a daily withdrawal limit
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum total a wallet may withdraw per calendar day
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_daily_withdrawal_limit(limit: i64) -> Self {
+        info!("Enforcing daily withdrawal limit of {}", limit);
+        WalletTerminal {
+            daily_withdrawal_limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that checks entered addresses against custom rules
+    ///
+    /// # Arguments
+    /// * `rules` - Rules wallet addresses must satisfy
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_address_rules(rules: AddressRules) -> Self {
+        info!("Using address rules {:?}", rules);
+        WalletTerminal {
+            address_rules: rules,
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new terminal instance that rejects transactions above a ceiling
+    ///
+    /// # Arguments
+    /// * `limit` - Largest amount a single transaction may move (plain integers are minor units)
+    ///
+    /// # Returns
+    /// * `Self` - Configured terminal instance ready for operation
+    pub fn with_max_amount(limit: impl Into<Money>) -> Self {
+        let limit = limit.into();
+        info!("Enforcing maximum transaction amount of {}", limit);
+        WalletTerminal {
+            max_transaction_amount: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the transactions recorded in the current session
+    ///
+    /// # Returns
+    /// * `io::Result<Vec<Transaction>>` - Every stored transaction, or the store's read error
+    pub fn transactions(&self) -> io::Result<Vec<Transaction>> {
+        self.store.all()
+    }
+
+    /// Calculates a wallet's current balance from the transactions the store holds for it
+    ///
+    /// With an indexed store only the wallet's own transactions are read.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet to calculate balance for
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - Balance in minor units, or the store or validation error
+    pub fn wallet_balance(&self, wallet_address: &str) -> Result<i64, WalletError> {
+        calculate_wallet_balance(&self.store.for_wallet(wallet_address)?, wallet_address)
+    }
+
+    /// Validates and appends a transaction to the session log
+    ///
+    /// The transaction is only kept if the affected wallets' histories remain valid
+    /// with it applied, so the log never contains an overdraft, overflow, or invalid
+    /// amount. Transfers are checked against both the source and the destination.
+    /// Amounts above a configured maximum and transactions reusing a recorded
+    /// idempotency key are rejected before validation.
+    ///
+    /// # Arguments
+    /// * `tx` - Transaction to record
+    ///
+    /// # Returns
+    /// * `Result<(), WalletError>` - Success, or the validation error that rejected the transaction
+    pub fn record_transaction(&mut self, tx: Transaction) -> Result<(), WalletError> {
+        self.record(tx).map(|_| ())
+    }
+
+    /// Records a transaction as `record_transaction` does, returning the balance of the
+    /// transaction's wallet computed while validating it
+    fn record(&mut self, tx: Transaction) -> Result<i64, WalletError> {
+        let wallet_address = tx.wallet_address.clone();
+        let is_withdrawal = matches!(tx.transaction_type, TransactionType::Withdrawal);
+        if is_withdrawal {
+            self.metrics.withdrawal_attempts += 1;
+        }
+        if let Some(key) = tx.idempotency_key.as_ref().filter(|key| self.seen_keys.contains(*key)) {
+            error!("Rejected transaction for wallet {}: key '{}' already recorded", wallet_address, key);
+            return Err(WalletError::DuplicateTransaction(key.clone()));
+        }
+        if let Some(limit) = self.max_transaction_amount {
+            if tx.amount > limit {
+                error!(
+                    "Rejected transaction for wallet {}: amount {} exceeds limit {}",
+                    wallet_address, tx.amount, limit
+                );
+                return Err(WalletError::AmountExceedsLimit {
+                    amount: tx.amount.minor_units(),
+                    limit: limit.minor_units(),
+                });
+            }
+        }
+
+        let mut history = self.store.for_wallet(&wallet_address)?;
+        debug!(
+            "Validating transaction {} against {} prior transactions for wallet {}",
+            tx.id,
+            history.len(),
+            wallet_address
+        );
+        history.push(tx.clone());
+
+        let balance = match self.validate_latest(&history, &wallet_address) {
+            Ok(balance) => balance,
+            Err(e) => {
+                error!("Rejected transaction for wallet {}: {}", wallet_address, e);
+                return Err(e);
+            }
+        };
+
+        // A transfer must also leave the receiving wallet's history valid
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            if *to != wallet_address {
+                let mut destination_history = self.store.for_wallet(to)?;
+                destination_history.push(tx.clone());
+                if let Err(e) = calculate_wallet_balance(&destination_history, to) {
+                    error!("Rejected transfer from wallet {} to {}: {}", wallet_address, to, e);
+                    return Err(e);
+                }
+            }
+        }
+        let key = tx.idempotency_key.clone();
+        self.store.append(tx)?;
+        self.seen_keys.extend(key);
+        if is_withdrawal {
+            self.metrics.withdrawals_recorded += 1;
+        }
+        Ok(balance)
+    }
+
+    /// Fraction of the session's withdrawal attempts that were recorded
+    ///
+    /// Every withdrawal submitted to `record_transaction` or `add_withdrawal`, or
+    /// entered through the menu, counts as an attempt, including ones rejected for
+    /// a non-positive amount, insufficient funds or limits. Script lines whose
+    /// amount is not a positive number are rejected as malformed commands and do
+    /// not count.
+    ///
+    /// # Returns
+    /// * `Option<f64>` - Recorded withdrawals divided by attempts, or None if no
+    ///   withdrawal was attempted
+    pub fn withdrawal_success_rate(&self) -> Option<f64> {
+        let SessionMetrics { withdrawal_attempts, withdrawals_recorded } = self.metrics;
+        if withdrawal_attempts == 0 {
+            return None;
+        }
+        let rate = withdrawals_recorded as f64 / withdrawal_attempts as f64;
+        info!("Withdrawal success rate: {}/{} ({:.2})", withdrawals_recorded, withdrawal_attempts, rate);
+        Some(rate)
+    }
+
+    /// Deposits into a wallet without any prompting
+    ///
+    /// # Arguments
+    /// * `wallet` - Address of the wallet receiving the funds
+    /// * `amount` - Amount to deposit (plain integers are minor units)
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - The wallet's new balance in minor units, `InvalidAmount`
+    ///   unless the amount is positive, or the validation error that rejected the deposit
+    pub fn add_deposit(&mut self, wallet: &str, amount: impl Into<Money>) -> Result<i64, WalletError> {
+        self.add_deposit_with_fee(wallet, amount.into(), Money::ZERO)
+    }
+
+    /// Deposits into a wallet, charging it a fee out of the deposited funds
+    fn add_deposit_with_fee(&mut self, wallet: &str, amount: Money, fee: Money) -> Result<i64, WalletError> {
+        let amount = Self::positive_amount(amount, "deposit")?;
+        self.record(Transaction {
+            fee: fee.minor_units(),
+            ..Transaction::new(TransactionType::Deposit, wallet, amount)
+        })
+    }
+
+    /// Withdraws from a wallet without any prompting
+    ///
+    /// # Arguments
+    /// * `wallet` - Address of the wallet paying out
+    /// * `amount` - Amount to withdraw (plain integers are minor units)
+    ///
+    /// # Returns
+    /// * `Result<i64, WalletError>` - The wallet's new balance in minor units, `InvalidAmount`
+    ///   unless the amount is positive, or the validation error that rejected the withdrawal
+    pub fn add_withdrawal(&mut self, wallet: &str, amount: impl Into<Money>) -> Result<i64, WalletError> {
+        self.add_withdrawal_with_fee(wallet, amount.into(), Money::ZERO)
+    }
+
+    /// Withdraws from a wallet, charging it a fee on top of the amount
+    fn add_withdrawal_with_fee(&mut self, wallet: &str, amount: Money, fee: Money) -> Result<i64, WalletError> {
+        let amount = match Self::positive_amount(amount, "withdrawal") {
+            Ok(amount) => amount,
+            Err(e) => {
+                // Rejected before reaching record, which counts every other attempt
+                self.metrics.withdrawal_attempts += 1;
+                return Err(e);
+            }
+        };
+        self.record(Transaction {
+            fee: fee.minor_units(),
+            ..Transaction::new(TransactionType::Withdrawal, wallet, amount)
+        })
+    }
+
+    /// Rejects amounts that are zero or negative
+    fn positive_amount(amount: Money, operation: &str) -> Result<Money, WalletError> {
+        if amount <= Money::ZERO {
+            error!("Invalid {} amount attempted: {}", operation, amount);
+            return Err(WalletError::InvalidAmount(amount.minor_units()));
+        }
+        Ok(amount)
+    }
+
+    /// Validates a wallet's history ending with a newly added transaction, returning its balance
+    fn validate_latest(&self, history: &[Transaction], wallet_address: &str) -> Result<i64, WalletError> {
+        let balance = calculate_wallet_balance(history, wallet_address)?;
+
+        // Only the day of a new withdrawal, outgoing transfer or fee can have newly
+        // breached the limit
+        if let (Some(limit), Some(latest)) = (self.daily_withdrawal_limit, history.last()) {
+            if latest.wallet_address == wallet_address && daily_outflow(latest, wallet_address) > 0 {
+                let date = latest.timestamp.date_naive();
+                if let Err(breaches) =
+                    validate_daily_withdrawal_limit(history, wallet_address, limit)
+                {
+                    if breaches.contains(&date) {
+                        return Err(WalletError::DailyLimitExceeded { date, limit });
+                    }
+                }
+            }
+        }
+        Ok(balance)
+    }
+
+    /// Saves a named copy of the current transaction log
+    ///
+    /// Reusing a name replaces the earlier checkpoint.
+    ///
+    /// # Arguments
+    /// * `name` - Name to store the checkpoint under
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success, or the store's read error
+    pub fn checkpoint(&mut self, name: &str) -> io::Result<()> {
+        let snapshot = self.store.all()?;
+        info!("Creating checkpoint '{}' at {} transactions", name, snapshot.len());
+        self.checkpoints.insert(name.to_string(), snapshot);
+        Ok(())
+    }
+
+    /// Restores the transaction log saved under a checkpoint name
+    ///
+    /// # Arguments
+    /// * `name` - Name of the checkpoint to restore
+    ///
+    /// # Returns
+    /// * `io::Result<bool>` - True if the checkpoint existed and was restored, or the
+    ///   store's write error
+    pub fn rewind_to(&mut self, name: &str) -> io::Result<bool> {
+        match self.checkpoints.get(name) {
+            Some(snapshot) => {
+                info!("Rewinding to checkpoint '{}' ({} transactions)", name, snapshot.len());
+                self.store.replace_all(snapshot.clone())?;
+                self.seen_keys = recorded_keys(snapshot);
+                Ok(true)
+            }
+            None => {
+                error!("Unknown checkpoint requested: {}", name);
+                println!("No checkpoint named '{}'", name);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Removes the most recently recorded transaction from the log
+    ///
+    /// Balances are recomputed from the log, so undoing a withdrawal makes its
+    /// funds available again. The transaction's idempotency key is released.
+    /// A store that fails to remove the transaction is logged and leaves the log
+    /// unchanged.
+    ///
+    /// # Returns
+    /// * `Option<Transaction>` - The removed transaction, or None if the log was
+    ///   empty or the store failed
+    pub fn undo_last(&mut self) -> Option<Transaction> {
+        let removed = match self.store.pop_last() {
+            Ok(removed) => removed,
+            Err(e) => {
+                error!("Failed to undo last transaction: {}", e);
+                return None;
+            }
+        };
+        match &removed {
+            Some(tx) => {
+                info!("Undid transaction {}: {}", tx.id, tx);
+                if let Some(key) = &tx.idempotency_key {
+                    self.seen_keys.remove(key);
+                }
+            }
+            None => info!("Undo requested with no transactions recorded"),
+        }
+        removed
+    }
+
+    /// Replays transactions one at a time with a pause between each step
+    ///
+    /// Intended for live demonstrations; see `replay_with_sleeper` for details.
+    ///
+    /// # Arguments
+    /// * `history` - Transactions to apply in order
+    /// * `delay` - Pause after each transaction
+    pub fn replay_with_delay(&mut self, history: &[Transaction], delay: Duration) {
+        self.replay_with_sleeper(history, delay, &mut ThreadSleeper);
+    }
+
+    /// Replays transactions one at a time, pausing through the given sleeper
+    ///
+    /// Each transaction is recorded and the affected wallet's balance is printed
+    /// so an audience can follow along. Rejected transactions are reported and
+    /// skipped without stopping the replay.
+    ///
+    /// # Arguments
+    /// * `history` - Transactions to apply in order
+    /// * `delay` - Pause after each transaction
+    /// * `sleeper` - Source of the pauses
+    pub fn replay_with_sleeper(
+        &mut self,
+        history: &[Transaction],
+        delay: Duration,
+        sleeper: &mut impl Sleeper,
+    ) {
+        info!("Replaying {} transactions with a {:?} delay", history.len(), delay);
+        for (step, tx) in history.iter().enumerate() {
+            let wallet_address = tx.wallet_address.clone();
+            match self.record_transaction(tx.clone()) {
+                Ok(()) => {
+                    let balance = self
+                        .store
+                        .for_wallet(&wallet_address)
+                        .ok()
+                        .and_then(|history| calculate_wallet_balance(&history, &wallet_address).ok())
+                        .unwrap_or_default();
+                    println!(
+                        "Step {}: {} | Balance of {}: {}",
+                        step + 1,
+                        tx,
+                        wallet_address,
+                        Money::from_minor(balance)
+                    );
+                }
+                Err(e) => println!("Step {}: {} rejected: {}", step + 1, tx, e),
+            }
+            sleeper.sleep(delay);
         }
     }
 
     /// Initializes the logging system for terminal operations
+    ///
+    /// The level comes from `$WALLET_LOG_LEVEL`, defaulting to info.
     /// 
     /// # Returns
     /// * `io::Result<()>` - Success or failure of logging setup
     fn init_logging() -> io::Result<()> {
         // Create logs directory structure
-        let log_dir = Path::new("logs/src");
-        fs::create_dir_all(log_dir)?;
+        let log_dir = log_directory("src");
+        fs::create_dir_all(&log_dir)?;
 
         // Generate timestamped log file path
         let log_file_path = log_dir.join(format!(
@@ -44,7 +732,8 @@ impl WalletTerminal {
             Local::now().format("%Y%m%d_%H%M%S")
         ));
 
-        // Configure and initialize logging
+        // Configure and initialize logging at the level requested in the environment
+        let level = parse_log_level(std::env::var(LOG_LEVEL_ENV).ok().as_deref());
         fern::Dispatch::new()
             .format(|out, message, record| {
                 out.finish(format_args!(
@@ -54,18 +743,36 @@ impl WalletTerminal {
                     message
                 ))
             })
-            .level(log::LevelFilter::Info)
-            .chain(fern::log_file(log_file_path)?)
+            .level(level)
+            .chain(fern::log_file(&log_file_path)?)
             .apply()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
 
+        debug!("Terminal logging at level {} to {}", level, log_file_path.display());
+        let _ = ACTIVE_LOG_FILE.set(log_file_path);
         Ok(())
     }
 
+    /// Reads the most recent lines of the active terminal log file
+    ///
+    /// # Arguments
+    /// * `n` - Maximum number of lines to return
+    ///
+    /// # Returns
+    /// * `io::Result<Vec<String>>` - Up to `n` final log lines, or an empty Vec if no
+    ///   log file is active
+    pub fn tail_log(&self, n: usize) -> io::Result<Vec<String>> {
+        match ACTIVE_LOG_FILE.get() {
+            Some(path) => tail_lines(path, n),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Starts the interactive terminal session
     pub fn run(&mut self) {
         info!("Starting wallet terminal session");
         println!("Welcome to Ryz Labs Wallet Terminal!");
+        self.restore_state();
         
         // Main interaction loop
         loop {
@@ -85,6 +792,114 @@ impl WalletTerminal {
         }
     }
 
+    /// Runs a command script as a full session
+    ///
+    /// The transaction log is restored from the state file before the script
+    /// runs and saved back afterwards, as an interactive session would.
+    ///
+    /// # Arguments
+    /// * `reader` - Source of script lines, such as stdin or a file
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success, or an error reading the script
+    pub fn run_script_session(&mut self, reader: impl BufRead) -> io::Result<()> {
+        self.restore_state();
+        let result = self.run_script(reader);
+        self.save_state();
+        result
+    }
+
+    /// Runs a sequence of commands without the interactive menu
+    ///
+    /// Each line holds one command:
+    /// `deposit <wallet> <amount>`, `withdraw <wallet> <amount>`,
+    /// `balance <wallet>`, or `transfer <from> <to> <amount>`.
+    /// Blank lines and lines starting with `#` are skipped. Addresses are
+    /// checked against the terminal's address rules. Invalid commands are
+    /// logged and reported, and the script continues with the next line.
+    ///
+    /// # Arguments
+    /// * `reader` - Source of script lines, such as stdin or a file
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success, or an error reading the script
+    pub fn run_script(&mut self, reader: impl BufRead) -> io::Result<()> {
+        info!("Starting wallet script session");
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let command = line.trim();
+            if command.is_empty() || command.starts_with('#') {
+                continue;
+            }
+            match self.execute_command(command) {
+                Ok(message) => println!("{}", message),
+                Err(message) => {
+                    error!("Script line {} failed: {}: {}", number + 1, command, message);
+                    println!("Line {}: {}", number + 1, message);
+                }
+            }
+        }
+        info!("Finished wallet script session");
+        Ok(())
+    }
+
+    /// Executes a single script command
+    ///
+    /// # Returns
+    /// * `Result<String, String>` - Message describing the outcome, or why the command failed
+    fn execute_command(&mut self, command: &str) -> Result<String, String> {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let address = |text: &str| self.address_rules.validate(text).map_err(|e| e.to_string());
+        let amount = |text: &str| {
+            self.parse_money_amount(text)
+                .filter(|amount| *amount > Money::ZERO)
+                .ok_or_else(|| format!("Invalid amount: {}", text))
+        };
+
+        let (tx, success) = match parts.as_slice() {
+            ["balance", wallet] => {
+                address(wallet)?;
+                let history = self.store.for_wallet(wallet).map_err(|e| e.to_string())?;
+                return calculate_wallet_balance(&history, wallet)
+                    .map(|balance| format!("Balance for wallet {}: {}", wallet, Money::from_minor(balance)))
+                    .map_err(|e| e.to_string());
+            }
+            ["deposit", wallet, value] => {
+                address(wallet)?;
+                let amount = amount(value)?;
+                (
+                    Transaction::new(TransactionType::Deposit, *wallet, amount),
+                    format!("Deposited {} to {}", amount, wallet),
+                )
+            }
+            ["withdraw", wallet, value] => {
+                address(wallet)?;
+                let amount = amount(value)?;
+                (
+                    Transaction::new(TransactionType::Withdrawal, *wallet, amount),
+                    format!("Withdrew {} from {}", amount, wallet),
+                )
+            }
+            ["transfer", from, to, value] => {
+                address(from)?;
+                address(to)?;
+                let amount = amount(value)?;
+                if from == to {
+                    return Err(String::from("Source and destination wallets must differ"));
+                }
+                (
+                    Transaction::new(TransactionType::Transfer { to: to.to_string() }, *from, amount),
+                    format!("Transferred {} from {} to {}", amount, from, to),
+                )
+            }
+            _ => return Err(format!("Unrecognized command: {}", command)),
+        };
+
+        self.record_transaction(tx).map_err(|e| e.to_string())?;
+        info!("Script command succeeded: {}", command);
+        Ok(success)
+    }
+
     /// Displays menu and processes user input
     /// 
     /// # Returns
@@ -97,14 +912,30 @@ impl WalletTerminal {
         println!("3. Withdraw");
         println!("4. View Transaction History");
         println!("5. Exit");
-        print!("\nEnter your choice (1-5): ");
+        println!("6. Undo Last Transaction");
+        println!("7. Create Checkpoint");
+        println!("8. Rewind to Checkpoint");
+        println!("9. Rankings");
+        println!("10. Recent Log Activity");
+        println!("11. Transfer");
+        println!("12. Export History to CSV");
+        println!("13. List Wallets");
+        println!("14. Wallet Summary");
+        print!("\nEnter your choice (1-14): ");
         io::stdout().flush()?;
 
-        // Process user input
-        let mut choice = String::new();
-        io::stdin().read_line(&mut choice)?;
+        // Process user input, treating end of input as a request to exit
+        let choice = match self.read_input_line()? {
+            Some(choice) => choice,
+            None => {
+                info!("Input closed, exiting");
+                self.save_state();
+                return Ok(true);
+            }
+        };
 
         // Handle menu selection
+        debug!("Menu choice entered: {:?}", choice.trim());
         match choice.trim() {
             "1" => {
                 info!("Selected: Check Balance");
@@ -124,8 +955,45 @@ impl WalletTerminal {
             }
             "5" => {
                 info!("Selected: Exit");
+                self.save_state();
                 return Ok(true);
             }
+            "6" => {
+                info!("Selected: Undo Last Transaction");
+                self.undo_transaction();
+            }
+            "7" => {
+                info!("Selected: Create Checkpoint");
+                self.create_checkpoint()?;
+            }
+            "8" => {
+                info!("Selected: Rewind to Checkpoint");
+                self.rewind_checkpoint()?;
+            }
+            "9" => {
+                info!("Selected: Rankings");
+                self.write_rankings(&mut io::stdout())?;
+            }
+            "10" => {
+                info!("Selected: Recent Log Activity");
+                self.show_recent_log()?;
+            }
+            "11" => {
+                info!("Selected: Transfer");
+                self.transfer()?;
+            }
+            "12" => {
+                info!("Selected: Export History to CSV");
+                self.export_history()?;
+            }
+            "13" => {
+                info!("Selected: List Wallets");
+                self.write_wallet_list(&mut io::stdout())?;
+            }
+            "14" => {
+                info!("Selected: Wallet Summary");
+                self.show_wallet_summary()?;
+            }
             _ => {
                 error!("Invalid menu choice entered: {}", choice.trim());
                 println!("Invalid choice. Please try again.");
@@ -135,52 +1003,144 @@ impl WalletTerminal {
         Ok(false)
     }
 
+    /// Saves the transaction log to the state file, reporting any failure
+    fn save_state(&self) {
+        let Some(state_path) = &self.state_path else {
+            return;
+        };
+        if let Err(e) = self.save_to_file(state_path) {
+            error!("Failed to save state to {}: {}", state_path.display(), e);
+            println!("Warning: Failed to save state: {}", e);
+        }
+    }
+
+    /// Reads one line of user input
+    ///
+    /// # Returns
+    /// * `io::Result<Option<String>>` - The line without its trailing newline, or None at end of input
+    fn read_input_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
     /// Gets wallet address from user input
     /// 
     /// # Returns
-    /// * `io::Result<String>` - Validated wallet address
-    fn get_wallet_address(&self) -> io::Result<String> {
-        print!("Enter wallet address: ");
+    /// * `io::Result<Option<String>>` - Validated wallet address, or None if it was rejected
+    fn get_wallet_address(&mut self) -> io::Result<Option<String>> {
+        self.prompt_wallet_address("Enter wallet address: ")
+    }
+
+    /// Gets a wallet address from user input using a custom prompt
+    ///
+    /// Addresses breaking the terminal's address rules are reported to the user.
+    ///
+    /// # Arguments
+    /// * `prompt` - Text shown before reading the address
+    ///
+    /// # Returns
+    /// * `io::Result<Option<String>>` - Validated wallet address, or None if it was rejected
+    fn prompt_wallet_address(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        print!("{}", prompt);
         io::stdout().flush()?;
-        let mut address = String::new();
-        io::stdin().read_line(&mut address)?;
-        let address = address.trim().to_string();
+        let address = self.read_input_line()?.unwrap_or_default().trim().to_string();
+        if let Err(e) = self.address_rules.validate(&address) {
+            println!("Error: {}", e);
+            return Ok(None);
+        }
         info!("Wallet address entered: {}", address);
-        Ok(address)
+        Ok(Some(address))
     }
 
     /// Gets transaction amount from user input
-    /// 
+    ///
+    /// Unreadable input is reported and the prompt repeated, up to three attempts.
+    /// Amounts are entered in major units, so "10.50" is 1050 minor units.
+    /// Whether the amount is acceptable for an operation (e.g. positive) is left
+    /// to the caller.
+    ///
     /// # Returns
-    /// * `io::Result<i64>` - Validated transaction amount
-    fn get_amount(&self) -> io::Result<i64> {
-        print!("Enter amount: ");
-        io::stdout().flush()?;
-        let mut amount_str = String::new();
-        io::stdin().read_line(&mut amount_str)?;
-        match amount_str.trim().parse() {
-            Ok(amount) => {
+    /// * `io::Result<Option<Money>>` - Entered amount, or None if no valid amount was
+    ///   entered within the allowed attempts or input ended
+    pub fn get_amount(&mut self) -> io::Result<Option<Money>> {
+        for attempt in 1..=MAX_AMOUNT_ATTEMPTS {
+            print!("Enter amount: ");
+            io::stdout().flush()?;
+            let Some(amount_str) = self.read_input_line()? else {
+                error!("Input ended while waiting for an amount");
+                return Ok(None);
+            };
+            if let Some(amount) = self.parse_money_amount(&amount_str) {
                 info!("Amount entered: {}", amount);
-                Ok(amount)
+                return Ok(Some(amount));
             }
-            Err(_) => {
-                error!("Invalid amount entered: {}", amount_str.trim());
-                println!("Invalid amount. Please enter a valid number.");
-                Ok(0)
+            error!(
+                "Invalid amount entered (attempt {} of {}): {}",
+                attempt,
+                MAX_AMOUNT_ATTEMPTS,
+                amount_str.trim()
+            );
+            println!("Invalid amount. Please enter a valid number.");
+        }
+        println!("No valid amount entered after {} attempts", MAX_AMOUNT_ATTEMPTS);
+        Ok(None)
+    }
+
+    /// Gets an optional transaction fee from user input
+    ///
+    /// Fees are entered in major units like amounts; a blank line means no fee.
+    ///
+    /// # Returns
+    /// * `io::Result<Option<Money>>` - Entered fee, or None if the fee was invalid or input ended
+    pub fn get_fee(&mut self) -> io::Result<Option<Money>> {
+        print!("Enter fee (blank for none): ");
+        io::stdout().flush()?;
+        let Some(fee_str) = self.read_input_line()? else {
+            error!("Input ended while waiting for a fee");
+            return Ok(None);
+        };
+        if fee_str.trim().is_empty() {
+            return Ok(Some(Money::ZERO));
+        }
+        match self.parse_money_amount(&fee_str) {
+            Some(fee) if !fee.is_negative() => {
+                info!("Fee entered: {}", fee);
+                Ok(Some(fee))
+            }
+            _ => {
+                error!("Invalid fee entered: {}", fee_str.trim());
+                println!("Invalid fee. Please enter zero or a positive amount.");
+                Ok(None)
             }
         }
     }
 
+    /// Reads an amount in major units using the terminal's number locale
+    ///
+    /// # Returns
+    /// * `Option<Money>` - Parsed amount, or None if the text is not an amount with at most
+    ///   two decimal places that fits in minor units
+    fn parse_money_amount(&self, text: &str) -> Option<Money> {
+        let parsed = parse_money(text, &self.number_locale);
+        debug!("Parsed amount {:?} with {:?}: {:?}", text.trim(), self.number_locale, parsed);
+        parsed.ok()
+    }
+
     /// Processes balance check request
     /// 
     /// # Returns
     /// * `io::Result<()>` - Success or failure of operation
-    fn check_balance(&self) -> io::Result<()> {
-        let wallet_address = self.get_wallet_address()?;
-        match calculate_wallet_balance(&self.transactions, &wallet_address) {
+    fn check_balance(&mut self) -> io::Result<()> {
+        let Some(wallet_address) = self.get_wallet_address()? else {
+            return Ok(());
+        };
+        match self.wallet_balance(&wallet_address) {
             Ok(balance) => {
                 info!("Balance check successful for {}: {}", wallet_address, balance);
-                println!("Balance for wallet {}: {}", wallet_address, balance);
+                println!("Balance for wallet {}: {}", wallet_address, Money::from_minor(balance));
             }
             Err(e) => {
                 error!("Balance check failed for {}: {}", wallet_address, e);
@@ -190,27 +1150,77 @@ impl WalletTerminal {
         Ok(())
     }
 
+    /// Prompts for a wallet address and prints its activity summary
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of operation
+    fn show_wallet_summary(&mut self) -> io::Result<()> {
+        let Some(wallet_address) = self.get_wallet_address()? else {
+            return Ok(());
+        };
+        self.write_wallet_summary(&wallet_address, &mut io::stdout())
+    }
+
+    /// Writes a readable block of a wallet's activity figures
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet to summarize
+    /// * `out` - Destination for the summary
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    pub fn write_wallet_summary(&self, wallet_address: &str, out: &mut impl Write) -> io::Result<()> {
+        let summary = match wallet_summary(&self.store.for_wallet(wallet_address)?, wallet_address) {
+            Ok(summary) => summary,
+            Err(e) => {
+                error!("Wallet summary failed for {}: {}", wallet_address, e);
+                writeln!(out, "Error summarizing wallet: {}", e)?;
+                return Ok(());
+            }
+        };
+
+        info!("Wallet summary shown for {}", wallet_address);
+        writeln!(out, "Summary for wallet {}", summary.wallet_address)?;
+        writeln!(out, "  Balance:           {}", Money::from_minor(summary.balance))?;
+        writeln!(out, "  Transactions:      {}", summary.transaction_count)?;
+        writeln!(out, "  Total deposits:    {}", Money::from_minor(summary.total_deposits))?;
+        writeln!(out, "  Total withdrawals: {}", Money::from_minor(summary.total_withdrawals))?;
+        match &summary.largest_transaction {
+            Some(tx) => writeln!(out, "  Largest:           {}", tx)?,
+            None => writeln!(out, "  Largest:           none")?,
+        }
+        Ok(())
+    }
+
     /// Processes deposit request
     /// 
     /// # Returns
     /// * `io::Result<()>` - Success or failure of operation
     fn deposit(&mut self) -> io::Result<()> {
-        let wallet_address = self.get_wallet_address()?;
-        let amount = self.get_amount()?;
-        
-        if amount <= 0 {
-            error!("Invalid deposit amount attempted: {}", amount);
-            println!("Amount must be positive");
+        let Some(wallet_address) = self.get_wallet_address()? else {
             return Ok(());
-        }
+        };
+        let Some(amount) = self.get_amount()? else {
+            return Ok(());
+        };
 
-        self.transactions.push(Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: wallet_address.clone(),
-            amount,
-        });
-        info!("Successful deposit of {} to wallet {}", amount, wallet_address);
-        println!("Successfully deposited {} to the wallet", amount);
+        let Some(fee) = self.get_fee()? else {
+            return Ok(());
+        };
+
+        match self.add_deposit_with_fee(&wallet_address, amount, fee) {
+            Ok(balance) => {
+                info!("Successful deposit of {} to wallet {}", amount, wallet_address);
+                println!("Successfully deposited {} to the wallet", amount);
+                self.report_fee(fee, &wallet_address);
+                println!("New balance: {}", Money::from_minor(balance));
+            }
+            Err(WalletError::InvalidAmount(_)) => println!("Amount must be positive"),
+            Err(e) => {
+                error!("Deposit error for wallet {}: {}", wallet_address, e);
+                println!("Error: {}", e);
+            }
+        }
         Ok(())
     }
 
@@ -219,28 +1229,29 @@ impl WalletTerminal {
     /// # Returns
     /// * `io::Result<()>` - Success or failure of operation
     fn withdraw(&mut self) -> io::Result<()> {
-        let wallet_address = self.get_wallet_address()?;
-        let amount = self.get_amount()?;
+        let Some(wallet_address) = self.get_wallet_address()? else {
+            return Ok(());
+        };
+        let Some(amount) = self.get_amount()? else {
+            return Ok(());
+        };
 
-        if amount <= 0 {
-            error!("Invalid withdrawal amount attempted: {}", amount);
-            println!("Amount must be positive");
+        let Some(fee) = self.get_fee()? else {
             return Ok(());
-        }
+        };
 
-        match calculate_wallet_balance(&self.transactions, &wallet_address) {
-            Ok(balance) if balance >= amount => {
-                self.transactions.push(Transaction {
-                    transaction_type: TransactionType::Withdrawal,
-                    wallet_address: wallet_address.clone(),
-                    amount,
-                });
+        match self.add_withdrawal_with_fee(&wallet_address, amount, fee) {
+            Ok(balance) => {
                 info!("Successful withdrawal of {} from wallet {}", amount, wallet_address);
                 println!("Successfully withdrew {} from the wallet", amount);
+                self.report_fee(fee, &wallet_address);
+                println!("New balance: {}", Money::from_minor(balance));
             }
-            Ok(balance) => {
-                error!("Insufficient funds for withdrawal: requested {}, available {}", amount, balance);
-                println!("Insufficient funds. Available balance: {}", balance);
+            Err(WalletError::InvalidAmount(_)) => println!("Amount must be positive"),
+            Err(WalletError::InsufficientFunds { requested, available }) => {
+                error!("Insufficient funds for withdrawal: requested {}, available {}", requested, available);
+                println!("Insufficient funds. Available balance: {}", Money::from_minor(available));
+                self.suggest_deposit(&wallet_address, requested);
             }
             Err(e) => {
                 error!("Withdrawal error for wallet {}: {}", wallet_address, e);
@@ -250,14 +1261,268 @@ impl WalletTerminal {
         Ok(())
     }
 
+    /// Tells the user how much to deposit before a rejected withdrawal can succeed
+    fn suggest_deposit(&self, wallet_address: &str, requested: i64) {
+        let history = self.store.for_wallet(wallet_address).unwrap_or_default();
+        if let Ok(needed) = shortfall(&history, wallet_address, requested) {
+            if needed > 0 {
+                info!("Suggested deposit of {} to wallet {}", needed, wallet_address);
+                println!("Deposit at least {} to complete this transaction", Money::from_minor(needed));
+            }
+        }
+    }
+
+    /// Logs and reports a fee charged for a recorded transaction
+    fn report_fee(&self, fee: Money, wallet_address: &str) {
+        if fee > Money::ZERO {
+            info!("Fee of {} charged to wallet {}", fee, wallet_address);
+            println!("Fee charged: {}", fee);
+        }
+    }
+
+    /// Prints the latest lines of the active log file
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of operation
+    fn show_recent_log(&self) -> io::Result<()> {
+        let lines = self.tail_log(RECENT_LOG_LINES)?;
+        if lines.is_empty() {
+            println!("No log activity recorded yet");
+        } else {
+            println!("Recent log activity:");
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes transfer request between two wallets
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of operation
+    fn transfer(&mut self) -> io::Result<()> {
+        let Some(source) = self.prompt_wallet_address("Enter source wallet address: ")? else {
+            return Ok(());
+        };
+        let Some(destination) = self.prompt_wallet_address("Enter destination wallet address: ")? else {
+            return Ok(());
+        };
+        let Some(amount) = self.get_amount()? else {
+            return Ok(());
+        };
+
+        if source == destination {
+            error!("Transfer attempted from wallet {} to itself", source);
+            println!("Source and destination wallets must differ");
+            return Ok(());
+        }
+        if amount <= Money::ZERO {
+            error!("Invalid transfer amount attempted: {}", amount);
+            println!("Amount must be positive");
+            return Ok(());
+        }
+        let Some(fee) = self.get_fee()? else {
+            return Ok(());
+        };
+
+        match self.record_transaction(Transaction {
+            fee: fee.minor_units(),
+            ..Transaction::new(TransactionType::Transfer { to: destination.clone() }, source.clone(), amount)
+        }) {
+            Ok(()) => {
+                info!("Transfer debit of {} from wallet {}", amount, source);
+                info!("Transfer credit of {} to wallet {}", amount, destination);
+                println!("Successfully transferred {} from {} to {}", amount, source, destination);
+                self.report_fee(fee, &source);
+            }
+            Err(WalletError::InsufficientFunds { requested, available }) => {
+                error!("Insufficient funds for transfer: requested {}, available {}", requested, available);
+                println!("Insufficient funds. Available balance: {}", Money::from_minor(available));
+                self.suggest_deposit(&source, requested);
+            }
+            Err(e) => {
+                error!("Transfer error from wallet {} to {}: {}", source, destination, e);
+                println!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
     /// Displays transaction history for a wallet
     /// 
     /// # Returns
     /// * `io::Result<()>` - Success or failure of operation
-    fn view_history(&self) -> io::Result<()> {
-        let wallet_address = self.get_wallet_address()?;
+    fn view_history(&mut self) -> io::Result<()> {
+        let Some(wallet_address) = self.get_wallet_address()? else {
+            return Ok(());
+        };
         info!("Viewing transaction history for wallet {}", wallet_address);
-        print_transaction_history(&self.transactions, &wallet_address);
+        print_transaction_history(&self.store.for_wallet(&wallet_address)?, &wallet_address);
+        Ok(())
+    }
+
+    /// Writes a wallet's transaction history to a CSV file
+    ///
+    /// # Arguments
+    /// * `wallet_address` - Address of the wallet to export
+    /// * `path` - File to write, replaced if it already exists
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the export
+    pub fn export_history_csv(&self, wallet_address: &str, path: &Path) -> io::Result<()> {
+        let history = self.store.for_wallet(wallet_address)?;
+        let mut writer = io::BufWriter::new(File::create(path)?);
+        export_csv(&history, wallet_address, &mut writer)?;
+        writer.flush()?;
+        info!("Exported history for wallet {} to {}", wallet_address, path.display());
+        Ok(())
+    }
+
+    /// Prompts for a wallet and file path and exports the wallet's history as CSV
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of reading input
+    fn export_history(&mut self) -> io::Result<()> {
+        let Some(wallet_address) = self.get_wallet_address()? else {
+            return Ok(());
+        };
+        print!("Enter export file path: ");
+        io::stdout().flush()?;
+        let path = self.read_input_line()?.unwrap_or_default().trim().to_string();
+        if path.is_empty() {
+            println!("No export path entered");
+            return Ok(());
+        }
+
+        match self.export_history_csv(&wallet_address, Path::new(&path)) {
+            Ok(()) => println!("Exported history for wallet {} to {}", wallet_address, path),
+            Err(e) => {
+                error!("CSV export to {} failed: {}", path, e);
+                println!("Error exporting history: {}", e);
+            }
+        }
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// Builds a read-only report of the session's balances and statistics
+    ///
+    /// # Returns
+    /// * `Result<Report, WalletError>` - Snapshot of balances, global statistics, and the
+    ///   history hash, or the store or validation error that prevented building it
+    pub fn export_report(&self) -> Result<Report, WalletError> {
+        let report = self
+            .store
+            .all()
+            .map_err(WalletError::from)
+            .and_then(|transactions| build_report(&transactions));
+        if let Err(e) = &report {
+            error!("Failed to build session report: {}", e);
+        }
+        report
+    }
+
+    /// Writes every wallet with its balance, highest balance first
+    ///
+    /// # Arguments
+    /// * `out` - Destination for the rankings listing
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    pub fn write_rankings(&self, out: &mut impl Write) -> io::Result<()> {
+        match wallets_by_balance(&self.store.all()?) {
+            Ok(rankings) if rankings.is_empty() => {
+                info!("Rankings requested with no wallets recorded");
+                writeln!(out, "No wallets recorded yet")?;
+            }
+            Ok(rankings) => {
+                info!("Displaying rankings for {} wallets", rankings.len());
+                writeln!(out, "Wallet rankings by balance:")?;
+                for (rank, (address, balance)) in rankings.iter().enumerate() {
+                    writeln!(out, "{}. {} | Balance: {}", rank + 1, address, Money::from_minor(*balance))?;
+                }
+            }
+            Err(e) => {
+                error!("Rankings failed: {}", e);
+                writeln!(out, "Error computing rankings: {}", e)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes every known wallet address with its current balance, in address order
+    ///
+    /// # Arguments
+    /// * `out` - Destination for the wallet listing
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of the write
+    pub fn write_wallet_list(&self, out: &mut impl Write) -> io::Result<()> {
+        let transactions = self.store.all()?;
+        let wallets = list_wallets(&transactions);
+        if wallets.is_empty() {
+            info!("Wallet list requested with no wallets recorded");
+            writeln!(out, "No wallets recorded yet")?;
+            return Ok(());
+        }
+
+        match calculate_all_balances(&transactions) {
+            Ok(balances) => {
+                info!("Listing {} wallets", wallets.len());
+                writeln!(out, "Known wallets:")?;
+                for wallet in &wallets {
+                    let balance = balances.get(wallet).copied().unwrap_or_default();
+                    writeln!(out, "{} | Balance: {}", wallet, Money::from_minor(balance))?;
+                }
+            }
+            Err(e) => {
+                error!("Wallet listing failed: {}", e);
+                writeln!(out, "Error computing balances: {}", e)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prompts for a name and saves a checkpoint under it
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of operation
+    fn create_checkpoint(&mut self) -> io::Result<()> {
+        let name = self.get_checkpoint_name()?;
+        self.checkpoint(&name)?;
+        println!("Checkpoint '{}' saved", name);
+        Ok(())
+    }
+
+    /// Undoes the last transaction and reports what was removed
+    fn undo_transaction(&mut self) {
+        match self.undo_last() {
+            Some(tx) => println!("Removed: {}", tx),
+            None => println!("No transactions to undo"),
+        }
+    }
+
+    /// Prompts for a name and rewinds the session to that checkpoint
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Success or failure of operation
+    fn rewind_checkpoint(&mut self) -> io::Result<()> {
+        let name = self.get_checkpoint_name()?;
+        if self.rewind_to(&name)? {
+            println!("Rewound to checkpoint '{}'", name);
+        }
+        Ok(())
+    }
+
+    /// Gets checkpoint name from user input
+    ///
+    /// # Returns
+    /// * `io::Result<String>` - Entered checkpoint name
+    fn get_checkpoint_name(&mut self) -> io::Result<String> {
+        print!("Enter checkpoint name: ");
+        io::stdout().flush()?;
+        let name = self.read_input_line()?.unwrap_or_default().trim().to_string();
+        info!("Checkpoint name entered: {}", name);
+        Ok(name)
+    }
+}
@@ -0,0 +1,185 @@
+use ryz_labs::export::*;
+use ryz_labs::*;
+use std::sync::Once;
+
+// Ensures logging initialization occurs only once across all test executions
+static INIT: Once = Once::new();
+
+// Initializes logging system with test-specific configuration
+fn initialize(test_name: &str) {
+    INIT.call_once(|| {
+        if let Err(e) = init_logging(test_name) {
+            eprintln!("Failed to initialize test logging: {}", e);
+        }
+    });
+}
+
+#[test]
+fn test_export_csv_neutralizes_formulas() {
+    // Test that memos starting with formula characters cannot execute in spreadsheets
+    initialize("test_export_csv_neutralizes_formulas");
+    log_section_header("Start Test: Export CSV Neutralizes Formulas");
+
+    let transactions = vec![
+        Transaction {
+            memo: Some(String::from("=cmd|' /C calc'!A0")),
+            ..Transaction::new(TransactionType::Deposit, "wallet_1", 100)
+        },
+        Transaction {
+            memo: Some(String::from("rent, \"march\"")),
+            ..Transaction::new(TransactionType::Withdrawal, "wallet_1", 30)
+        },
+    ];
+
+    let mut output = Vec::new();
+    export_csv_with_memos(&transactions, "wallet_1", &mut output).unwrap();
+    let csv = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], "type,wallet,amount,running_balance,memo");
+    assert_eq!(
+        lines[1],
+        "\"Deposit\",\"wallet_1\",1.00,1.00,\"'=cmd|' /C calc'!A0\""
+    );
+    assert_eq!(
+        lines[2],
+        "\"Withdrawal\",\"wallet_1\",0.30,0.70,\"rent, \"\"march\"\"\""
+    );
+
+    // The plain export leaves memos out entirely
+    let mut output = Vec::new();
+    export_csv(&transactions, "wallet_1", &mut output).unwrap();
+    let csv = String::from_utf8(output).unwrap();
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        vec![
+            "type,wallet,amount,running_balance",
+            "\"Deposit\",\"wallet_1\",1.00,1.00",
+            "\"Withdrawal\",\"wallet_1\",0.30,0.70",
+        ]
+    );
+
+    log_section_header("End Test: Export CSV Neutralizes Formulas");
+}
+
+#[test]
+fn test_sanitize_csv_field() {
+    // Test each formula trigger is prefixed while ordinary text is only quoted
+    for value in ["=1+1", "+1", "-1", "@SUM(A1)"] {
+        assert_eq!(sanitize_csv_field(value), format!("\"'{}\"", value));
+    }
+    assert_eq!(sanitize_csv_field("wallet_1"), "\"wallet_1\"");
+}
+
+// Creates an empty scratch directory unique to the given test
+fn scratch_dir(test_name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("ryz_labs_{}_{}", test_name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_export_all_writes_one_file_per_wallet() {
+    // Test that every wallet gets its own file with its own history
+    initialize("test_export_all_writes_one_file_per_wallet");
+    log_section_header("Start Test: Export All Writes One File Per Wallet");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_1", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_2", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_1", 40),
+    ];
+
+    let csv_dir = scratch_dir("export_all_csv");
+    let paths = export_all(&transactions, &csv_dir, ExportFormat::Csv).unwrap();
+    assert_eq!(paths, vec![csv_dir.join("wallet_1.csv"), csv_dir.join("wallet_2.csv")]);
+
+    let wallet_1 = std::fs::read_to_string(&paths[0]).unwrap();
+    let lines: Vec<&str> = wallet_1.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2], "\"Withdrawal\",\"wallet_1\",0.40,0.60");
+    let wallet_2 = std::fs::read_to_string(&paths[1]).unwrap();
+    assert_eq!(wallet_2.lines().nth(1), Some("\"Deposit\",\"wallet_2\",0.50,0.50"));
+
+    let json_dir = scratch_dir("export_all_json");
+    let paths = export_all(&transactions, &json_dir, ExportFormat::Json).unwrap();
+    assert_eq!(paths.len(), 2);
+    let entries: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&paths[0]).unwrap()).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["wallet_address"], "wallet_1");
+    assert_eq!(entries[1]["amount"], 40);
+    assert_eq!(entries[1]["running_balance"], 60);
+
+    std::fs::remove_dir_all(&csv_dir).unwrap();
+    std::fs::remove_dir_all(&json_dir).unwrap();
+    log_section_header("End Test: Export All Writes One File Per Wallet");
+}
+
+#[test]
+fn test_export_all_suffixes_colliding_file_names() {
+    // Test that addresses sanitizing to the same name do not overwrite each other
+    initialize("test_export_all_suffixes_colliding_file_names");
+    log_section_header("Start Test: Export All Suffixes Colliding File Names");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet/a", 10),
+        Transaction::new(TransactionType::Deposit, "wallet:a", 20),
+    ];
+
+    let dir = scratch_dir("export_all_collisions");
+    let paths = export_all(&transactions, &dir, ExportFormat::Csv).unwrap();
+    assert_eq!(paths, vec![dir.join("wallet_a.csv"), dir.join("wallet_a_2.csv")]);
+    assert!(std::fs::read_to_string(&paths[1]).unwrap().contains("\"wallet:a\",0.20,0.20"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    log_section_header("End Test: Export All Suffixes Colliding File Names");
+}
+
+#[test]
+fn test_write_transaction_history_formats() {
+    // Test that each history format writes the same two-transaction wallet
+    initialize("test_write_transaction_history_formats");
+    log_section_header("Start Test: Write Transaction History Formats");
+
+    let transactions = vec![
+        Transaction {
+            memo: Some(String::from("salary")),
+            ..Transaction::new(TransactionType::Deposit, "history_1", 1000)
+        },
+        Transaction::new(TransactionType::Deposit, "history_2", 70),
+        Transaction::new(TransactionType::Transfer { to: String::from("history_2") }, "history_1", 250),
+    ];
+    let render = |format| {
+        let mut output = Vec::new();
+        write_transaction_history(&transactions, "history_1", format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    // JSON keeps amounts in minor units while plain text and CSV show major units
+    let entries: serde_json::Value = serde_json::from_str(&render(HistoryFormat::Json)).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["id"], transactions[0].id);
+    assert_eq!(entries[0]["transaction_type"], "Deposit");
+    assert_eq!(entries[0]["amount"], 1000);
+    assert_eq!(entries[0]["memo"], "salary");
+    assert_eq!(entries[0]["running_balance"], 1000);
+    assert_eq!(entries[1]["transaction_type"]["Transfer"]["to"], "history_2");
+    assert_eq!(entries[1]["wallet_address"], "history_1");
+    assert_eq!(entries[1]["running_balance"], 750);
+    assert_eq!(entries[1]["amount"], 250);
+
+    let csv = render(HistoryFormat::Csv);
+    assert_eq!(csv.lines().count(), 3);
+    assert_eq!(csv.lines().nth(2), Some("\"Transfer\",\"history_1\",2.50,7.50"));
+
+    let plain = render(HistoryFormat::Plain);
+    let lines: Vec<&str> = plain.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "Transaction history for wallet history_1:");
+    assert!(lines[2].ends_with(" | Running balance: 7.50"));
+
+    log_section_header("End Test: Write Transaction History Formats");
+}
@@ -0,0 +1,160 @@
+use ryz_labs::money::*;
+use ryz_labs::*;
+use std::sync::Once;
+
+// Ensures logging initialization occurs only once across all test executions
+static INIT: Once = Once::new();
+
+// Initializes logging system with test-specific configuration
+fn initialize(test_name: &str) {
+    INIT.call_once(|| {
+        if let Err(e) = init_logging(test_name) {
+            eprintln!("Failed to initialize test logging: {}", e);
+        }
+    });
+}
+
+#[test]
+fn test_parse_amount_european_grouping() {
+    // Test that European locales treat '.' as a thousands separator
+    initialize("test_parse_amount_european_grouping");
+    log_section_header("Start Test: Parse Amount European Grouping");
+
+    assert_eq!(parse_amount("1.234", &NumberLocale::EUROPEAN).unwrap(), 1234.0);
+    assert_eq!(parse_amount("1.234,56", &NumberLocale::EUROPEAN).unwrap(), 1234.56);
+    assert_eq!(parse_amount("12,5", &NumberLocale::EUROPEAN).unwrap(), 12.5);
+
+    log_section_header("End Test: Parse Amount European Grouping");
+}
+
+#[test]
+fn test_parse_amount_us_decimal() {
+    // Test that US locales treat '.' as the decimal separator
+    initialize("test_parse_amount_us_decimal");
+    log_section_header("Start Test: Parse Amount US Decimal");
+
+    assert_eq!(parse_amount("1.234", &NumberLocale::US).unwrap(), 1.234);
+    assert_eq!(parse_amount("1,234.5", &NumberLocale::US).unwrap(), 1234.5);
+    assert_eq!(parse_amount(" 100 ", &NumberLocale::US).unwrap(), 100.0);
+    assert_eq!(parse_amount("-75", &NumberLocale::US).unwrap(), -75.0);
+
+    log_section_header("End Test: Parse Amount US Decimal");
+}
+
+#[test]
+fn test_parse_amount_rejects_foreign_formats() {
+    // Test that inputs are read strictly according to the configured locale
+    initialize("test_parse_amount_rejects_foreign_formats");
+    log_section_header("Start Test: Parse Amount Rejects Foreign Formats");
+
+    for (input, locale) in [
+        ("1.234,56", NumberLocale::US),
+        ("1,234.56", NumberLocale::EUROPEAN),
+        ("1.23", NumberLocale::EUROPEAN),
+        ("12,34", NumberLocale::US),
+        ("1.2.3", NumberLocale::US),
+        ("abc", NumberLocale::US),
+        ("", NumberLocale::US),
+        ("5.", NumberLocale::US),
+    ] {
+        assert!(
+            matches!(parse_amount(input, &locale), Err(WalletError::UnparseableAmount(_))),
+            "expected '{}' to be rejected under {:?}",
+            input,
+            locale
+        );
+    }
+
+    log_section_header("End Test: Parse Amount Rejects Foreign Formats");
+}
+
+#[test]
+fn test_format_accounting() {
+    // Test that negatives use parentheses and positives render plainly
+    initialize("test_format_accounting");
+    log_section_header("Start Test: Format Accounting");
+
+    assert_eq!(format_accounting(-50), "(50)");
+    assert_eq!(format_accounting(100), "100");
+    assert_eq!(format_accounting(0), "0");
+    assert_eq!(format_accounting_with_symbol(-50, Some("$")), "($50)");
+    assert_eq!(format_accounting_with_symbol(100, Some("$")), "$100");
+    assert_eq!(format_accounting(i64::MIN), "(9223372036854775808)");
+
+    log_section_header("End Test: Format Accounting");
+}
+
+#[test]
+fn test_denominations_breaks_amount() {
+    // Test greedy change-making with a standard set of denominations
+    initialize("test_denominations_breaks_amount");
+    log_section_header("Start Test: Denominations Breaks Amount");
+
+    assert_eq!(
+        denominations(115, &[100, 50, 10, 5, 1]),
+        Some(vec![(100, 1), (10, 1), (5, 1)])
+    );
+    assert_eq!(denominations(280, &[5, 100, 20]), Some(vec![(100, 2), (20, 4)]));
+    assert_eq!(denominations(0, &[10]), Some(vec![]));
+
+    log_section_header("End Test: Denominations Breaks Amount");
+}
+
+#[test]
+fn test_denominations_rejects_inexact_amounts() {
+    // Test that amounts which cannot be made exactly yield None
+    initialize("test_denominations_rejects_inexact_amounts");
+    log_section_header("Start Test: Denominations Rejects Inexact Amounts");
+
+    assert_eq!(denominations(117, &[100, 50, 10, 5]), None);
+    assert_eq!(denominations(3, &[]), None);
+    assert_eq!(denominations(-5, &[1]), None);
+
+    log_section_header("End Test: Denominations Rejects Inexact Amounts");
+}
+
+#[test]
+fn test_money_constructors_and_display() {
+    // Test that money is stored in minor units and rendered with two decimals
+    initialize("test_money_constructors_and_display");
+    log_section_header("Start Test: Money Constructors And Display");
+
+    assert_eq!(Money::from_major(10).unwrap(), Money::from_minor(1000));
+    assert_eq!(Money::from_minor(1050).minor_units(), 1050);
+    assert_eq!(Money::from_minor(100).to_string(), "1.00");
+    assert_eq!(Money::from_minor(1050).to_string(), "10.50");
+    assert_eq!(Money::from_minor(-5).to_string(), "-0.05");
+    assert_eq!(Money::from_minor(i64::MIN).to_string(), "-92233720368547758.08");
+    assert_eq!(Money::from_major(i64::MAX), None);
+
+    assert_eq!(Money::from_minor(i64::MAX).checked_add(Money::from_minor(1)), None);
+    assert_eq!(
+        Money::from_minor(150).checked_sub(Money::from_minor(50)),
+        Some(Money::from_minor(100))
+    );
+
+    log_section_header("End Test: Money Constructors And Display");
+}
+
+#[test]
+fn test_parse_money_exact_minor_units() {
+    // Test that entered amounts map exactly onto minor units
+    initialize("test_parse_money_exact_minor_units");
+    log_section_header("Start Test: Parse Money Exact Minor Units");
+
+    assert_eq!(parse_money("10.50", &NumberLocale::US).unwrap(), Money::from_minor(1050));
+    assert_eq!(parse_money("10.5", &NumberLocale::US).unwrap(), Money::from_minor(1050));
+    assert_eq!(parse_money("1,234", &NumberLocale::US).unwrap(), Money::from_minor(123_400));
+    assert_eq!(parse_money("1.234,56", &NumberLocale::EUROPEAN).unwrap(), Money::from_minor(123_456));
+    assert_eq!(parse_money("-0.07", &NumberLocale::US).unwrap(), Money::from_minor(-7));
+
+    for input in ["1.005", "12x", "", "99999999999999999999"] {
+        assert!(
+            matches!(parse_money(input, &NumberLocale::US), Err(WalletError::UnparseableAmount(_))),
+            "expected {:?} to be rejected",
+            input
+        );
+    }
+
+    log_section_header("End Test: Parse Money Exact Minor Units");
+}
@@ -0,0 +1,72 @@
+use ryz_labs::shared::SharedWallet;
+use ryz_labs::*;
+use std::sync::Once;
+use std::thread;
+
+// Ensures logging initialization occurs only once across all test executions
+static INIT: Once = Once::new();
+
+// Initializes logging system with test-specific configuration
+fn initialize(test_name: &str) {
+    INIT.call_once(|| {
+        if let Err(e) = init_logging(test_name) {
+            eprintln!("Failed to initialize test logging: {}", e);
+        }
+    });
+}
+
+#[test]
+fn test_shared_wallet_concurrent_deposits() {
+    // Test that deposits from several threads all land in the shared log
+    initialize("test_shared_wallet_concurrent_deposits");
+    log_section_header("Start Test: Shared Wallet Concurrent Deposits");
+
+    let wallet = SharedWallet::new();
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let wallet = wallet.clone();
+            thread::spawn(move || {
+                for _ in 0..25 {
+                    wallet.deposit("shared_1", 4).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(wallet.balance("shared_1").unwrap(), 800);
+    assert_eq!(wallet.transactions().len(), 200);
+
+    log_section_header("End Test: Shared Wallet Concurrent Deposits");
+}
+
+#[test]
+fn test_shared_wallet_concurrent_withdrawals() {
+    // Test that racing withdrawals never overdraw the wallet
+    initialize("test_shared_wallet_concurrent_withdrawals");
+    log_section_header("Start Test: Shared Wallet Concurrent Withdrawals");
+
+    let wallet = SharedWallet::new();
+    wallet.deposit("shared_2", 100).unwrap();
+
+    let handles: Vec<_> = (0..10)
+        .map(|_| {
+            let wallet = wallet.clone();
+            thread::spawn(move || wallet.withdraw("shared_2", 30).is_ok())
+        })
+        .collect();
+    let succeeded = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+
+    assert_eq!(succeeded, 3);
+    assert_eq!(wallet.balance("shared_2").unwrap(), 10);
+    assert!(matches!(
+        wallet.withdraw("shared_2", 11),
+        Err(WalletError::InsufficientFunds { requested: 11, available: 10 })
+    ));
+    assert!(matches!(wallet.deposit("shared_2", -1), Err(WalletError::InvalidAmount(-1))));
+    assert_eq!(wallet.transactions().len(), 4);
+
+    log_section_header("End Test: Shared Wallet Concurrent Withdrawals");
+}
@@ -0,0 +1,145 @@
+use chrono::{Local, TimeZone};
+use ryz_labs::store::*;
+use ryz_labs::terminal::WalletTerminal;
+use ryz_labs::*;
+use std::sync::Once;
+
+// Ensures logging initialization occurs only once across all test executions
+static INIT: Once = Once::new();
+
+// Initializes logging system with test-specific configuration
+fn initialize(test_name: &str) {
+    INIT.call_once(|| {
+        if let Err(e) = init_logging(test_name) {
+            eprintln!("Failed to initialize test logging: {}", e);
+        }
+    });
+}
+
+// Transactions covering every type, status, and optional field a store must keep
+fn sample_transactions() -> Vec<Transaction> {
+    vec![
+        Transaction {
+            timestamp: Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            memo: Some(String::from("opening")),
+            ..Transaction::new(TransactionType::Deposit, "store_1", 500)
+        },
+        Transaction {
+            fee: 2,
+            status: TransactionStatus::Pending,
+            ..Transaction::new(TransactionType::Withdrawal, "store_1", 100)
+        },
+        Transaction::new(TransactionType::Transfer { to: String::from("store_2") }, "store_1", 150),
+        Transaction {
+            currency: String::from("EUR"),
+            idempotency_key: Some(String::from("store-key-1")),
+            ..Transaction::new(TransactionType::Deposit, "store_3", 75)
+        },
+    ]
+}
+
+// Runs the same operations against any store and checks what it returns
+fn exercise_store(store: &mut impl TransactionStore) {
+    let transactions = sample_transactions();
+    assert!(store.all().unwrap().is_empty());
+
+    for tx in transactions.clone() {
+        store.append(tx).unwrap();
+    }
+
+    assert_eq!(store.all().unwrap(), transactions);
+
+    // Wallet lookups include transfers the wallet receives, in insertion order
+    let ids = |wallet: &str| -> Vec<u64> {
+        store.for_wallet(wallet).unwrap().iter().map(|tx| tx.id).collect()
+    };
+    assert_eq!(ids("store_1"), vec![transactions[0].id, transactions[1].id, transactions[2].id]);
+    assert_eq!(ids("store_2"), vec![transactions[2].id]);
+    assert!(ids("store_unknown").is_empty());
+    assert_eq!(calculate_wallet_balance(&store.for_wallet("store_1").unwrap(), "store_1").unwrap(), 248);
+    assert_eq!(calculate_wallet_balance(&store.for_wallet("store_2").unwrap(), "store_2").unwrap(), 150);
+
+    assert_eq!(store.pop_last().unwrap(), transactions.last().cloned());
+    assert_eq!(store.all().unwrap(), transactions[..3]);
+
+    store.replace_all(transactions[..1].to_vec()).unwrap();
+    assert_eq!(store.all().unwrap().len(), 1);
+    assert!(store.for_wallet("store_2").unwrap().is_empty());
+
+    assert_eq!(store.pop_last().unwrap(), Some(transactions[0].clone()));
+    assert_eq!(store.pop_last().unwrap(), None);
+}
+
+#[test]
+fn test_vec_store() {
+    // Test the in-memory store against the shared store operations
+    initialize("test_vec_store");
+    log_section_header("Start Test: Vec Store");
+
+    let mut store: Vec<Transaction> = Vec::new();
+    exercise_store(&mut store);
+
+    log_section_header("End Test: Vec Store");
+}
+
+#[test]
+fn test_indexed_store() {
+    // Test the wallet-indexed store against the shared store operations
+    initialize("test_indexed_store");
+    log_section_header("Start Test: Indexed Store");
+
+    let mut store = IndexedStore::new();
+    exercise_store(&mut store);
+    assert_eq!(store.index(), &WalletIndex::build(store.transactions()));
+
+    log_section_header("End Test: Indexed Store");
+}
+
+#[test]
+fn test_sqlite_store() {
+    // Test the SQLite store against the shared store operations
+    initialize("test_sqlite_store");
+    log_section_header("Start Test: SQLite Store");
+
+    let mut store = SqliteStore::open_in_memory().unwrap();
+    exercise_store(&mut store);
+
+    log_section_header("End Test: SQLite Store");
+}
+
+#[test]
+fn test_sqlite_store_persists_across_reopen() {
+    // Test that a database file keeps its transactions and reserves their ids
+    initialize("test_sqlite_store_persists_across_reopen");
+    log_section_header("Start Test: SQLite Store Persists Across Reopen");
+
+    let path = std::env::temp_dir().join(format!("ryz_labs_store_{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let transactions = sample_transactions();
+    {
+        let mut store = SqliteStore::open(&path).unwrap();
+        for tx in transactions.clone() {
+            store.append(tx).unwrap();
+        }
+    }
+
+    let reopened = SqliteStore::open(&path).unwrap();
+    assert_eq!(reopened.all().unwrap().len(), transactions.len());
+    let fresh = Transaction::new(TransactionType::Deposit, "store_1", 1);
+    assert!(transactions.iter().all(|tx| tx.id < fresh.id));
+
+    // A terminal backed by the store validates against the persisted history
+    let mut terminal = WalletTerminal::with_store(reopened);
+    terminal
+        .record_transaction(Transaction::new(TransactionType::Withdrawal, "store_1", 48))
+        .unwrap();
+    assert!(matches!(
+        terminal.record_transaction(Transaction::new(TransactionType::Withdrawal, "store_1", 201)),
+        Err(WalletError::InsufficientFunds { requested: 201, available: 200 })
+    ));
+    assert_eq!(terminal.transactions().unwrap().len(), transactions.len() + 1);
+
+    let _ = std::fs::remove_file(&path);
+    log_section_header("End Test: SQLite Store Persists Across Reopen");
+}
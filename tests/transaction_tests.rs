@@ -1,4 +1,6 @@
+use chrono::{DateTime, Duration, Local, TimeZone};
 use ryz_labs::*;
+use ryz_labs::money::Money;
 use std::sync::Once;
 
 // Ensures logging initialization occurs only once across all test executions
@@ -7,10 +9,30 @@ static INIT: Once = Once::new();
 // Initializes logging system with test-specific configuration
 fn initialize(test_name: &str) {
     INIT.call_once(|| {
-        init_logging(test_name);
+        if let Err(e) = init_logging(test_name) {
+            eprintln!("Failed to initialize test logging: {}", e);
+        }
     });
 }
 
+// Builds a transaction stamped with a fixed time for time-based tests
+fn timestamped(
+    transaction_type: TransactionType,
+    wallet_address: &str,
+    amount: i64,
+    timestamp: DateTime<Local>,
+) -> Transaction {
+    Transaction {
+        timestamp,
+        ..Transaction::new(transaction_type, wallet_address, amount)
+    }
+}
+
+// Fixed reference time shared by time-based tests
+fn base_time() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+}
+
 #[test]
 fn test_transaction_creation() {
     // Initialize test environment with unique identifier
@@ -18,18 +40,10 @@ fn test_transaction_creation() {
     log_section_header("Start Test: Transaction Creation");
 
     // Create sample deposit transaction for testing
-    let deposit_transaction = Transaction {
-        transaction_type: TransactionType::Deposit,
-        wallet_address: String::from("wallet_1"),
-        amount: 100,
-    };
+    let deposit_transaction = Transaction::new(TransactionType::Deposit, "wallet_1", 100);
 
     // Create sample withdrawal transaction for testing
-    let withdrawal_transaction = Transaction {
-        transaction_type: TransactionType::Withdrawal,
-        wallet_address: String::from("wallet_2"),
-        amount: 50,
-    };
+    let withdrawal_transaction = Transaction::new(TransactionType::Withdrawal, "wallet_2", 50);
 
     // Verify deposit transaction properties
     match deposit_transaction.transaction_type {
@@ -37,7 +51,7 @@ fn test_transaction_creation() {
         _ => panic!("Expected Deposit transaction type"),
     }
     assert_eq!(deposit_transaction.wallet_address, "wallet_1");
-    assert_eq!(deposit_transaction.amount, 100);
+    assert_eq!(deposit_transaction.amount, Money::from_minor(100));
 
     // Verify withdrawal transaction properties
     match withdrawal_transaction.transaction_type {
@@ -45,7 +59,7 @@ fn test_transaction_creation() {
         _ => panic!("Expected Withdrawal transaction type"),
     }
     assert_eq!(withdrawal_transaction.wallet_address, "wallet_2");
-    assert_eq!(withdrawal_transaction.amount, 50);
+    assert_eq!(withdrawal_transaction.amount, Money::from_minor(50));
 
     log_section_header("End Test: Transaction Creation");
 }
@@ -58,16 +72,8 @@ fn test_calculate_wallet_balance() {
 
     // Create test transactions for balance calculation
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_1"),
-            amount: 100,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_1"),
-            amount: 30,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_1", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_1", 30),
     ];
 
     // Verify correct balance calculation
@@ -97,11 +103,7 @@ fn test_wallet_not_in_transactions() {
     log_section_header("Start Test: Wallet Not in Transactions");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_2"),
-            amount: 100,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_2", 100),
     ];
     let balance = calculate_wallet_balance(&transactions, "wallet_3").unwrap();
     assert_eq!(balance, 0);
@@ -116,16 +118,8 @@ fn test_multiple_deposits() {
     log_section_header("Start Test: Multiple Deposits");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_4"),
-            amount: 100,
-        },
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_4"),
-            amount: 200,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_4", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_4", 200),
     ];
     let balance = calculate_wallet_balance(&transactions, "wallet_4").unwrap();
     assert_eq!(balance, 300);
@@ -140,16 +134,8 @@ fn test_multiple_withdrawals() {
     log_section_header("Start Test: Multiple Withdrawals");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_5"),
-            amount: 50,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_5"),
-            amount: 30,
-        },
+        Transaction::new(TransactionType::Withdrawal, "wallet_5", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_5", 30),
     ];
     let result = calculate_wallet_balance(&transactions, "wallet_5");
     assert!(matches!(
@@ -167,26 +153,10 @@ fn test_mixed_transactions_multiple_wallets() {
     log_section_header("Start Test: Mixed Transactions Multiple Wallets");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_6"),
-            amount: 150,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_6"),
-            amount: 50,
-        },
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_7"),
-            amount: 200,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_7"),
-            amount: 100,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_6", 150),
+        Transaction::new(TransactionType::Withdrawal, "wallet_6", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_7", 200),
+        Transaction::new(TransactionType::Withdrawal, "wallet_7", 100),
     ];
     let balance_wallet_1 = calculate_wallet_balance(&transactions, "wallet_6").unwrap();
     let balance_wallet_2 = calculate_wallet_balance(&transactions, "wallet_7").unwrap();
@@ -204,11 +174,7 @@ fn test_invalid_transaction_amount() {
     log_section_header("Start Test: Invalid Transaction Amount");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_8"),
-            amount: -100,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_8", -100),
     ];
 
     let result = calculate_wallet_balance(&transactions, "wallet_8");
@@ -224,16 +190,8 @@ fn test_insufficient_funds() {
     log_section_header("Start Test: Insufficient Funds");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_9"),
-            amount: 50,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_9"),
-            amount: 100,
-        },
+        Transaction::new(TransactionType::Deposit, "wallet_9", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_9", 100),
     ];
 
     let result = calculate_wallet_balance(&transactions, "wallet_9");
@@ -251,12 +209,19 @@ fn test_insufficient_funds() {
 #[test]
 fn test_display_transaction() {
     // Test transaction display formatting
-    let transaction = Transaction {
-        transaction_type: TransactionType::Deposit,
-        wallet_address: String::from("wallet_1"),
-        amount: 100,
-    };
-    assert_eq!(format!("{}", transaction), "Deposit of 100 to wallet_1");
+    let transaction = timestamped(TransactionType::Deposit, "wallet_1", 10_000, base_time());
+    assert_eq!(format!("{}", transaction), "2024-01-01 12:00:00 | Deposit of 100.00 to wallet_1");
+
+    let transfer = timestamped(
+        TransactionType::Transfer { to: String::from("wallet_2") },
+        "wallet_1",
+        4_050,
+        base_time(),
+    );
+    assert_eq!(
+        format!("{}", transfer),
+        "2024-01-01 12:00:00 | Transfer of 40.50 from wallet_1 to wallet_2"
+    );
 }
 
 #[test]
@@ -266,29 +231,21 @@ fn test_print_transaction_history() {
     log_section_header("Start Test: Print Transaction History");
 
     let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: String::from("wallet_1"),
-            amount: 100,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: String::from("wallet_1"),
-            amount: 30,
-        },
+        timestamped(TransactionType::Deposit, "wallet_1", 100, base_time()),
+        timestamped(TransactionType::Withdrawal, "wallet_1", 30, base_time() + Duration::minutes(5)),
     ];
 
     // Verify transaction display format
     let transaction = &transactions[0];
     assert_eq!(
         format!("{}", transaction),
-        "Deposit of 100 to wallet_1"
+        "2024-01-01 12:00:00 | Deposit of 1.00 to wallet_1"
     );
 
     let transaction = &transactions[1];
     assert_eq!(
         format!("{}", transaction),
-        "Withdrawal of 30 to wallet_1"
+        "2024-01-01 12:05:00 | Withdrawal of 0.30 to wallet_1"
     );
 
     // Verify history printing functionality
@@ -297,137 +254,3092 @@ fn test_print_transaction_history() {
     log_section_header("End Test: Print Transaction History");
 }
 
-// Terminal-specific test module
-mod terminal_tests {
-    use super::*;
-    use ryz_labs::terminal::WalletTerminal;
+#[test]
+fn test_transaction_crossing_threshold() {
+    // Test detection of the transaction that pushes a balance past a threshold
+    initialize("test_transaction_crossing_threshold");
+    log_section_header("Start Test: Transaction Crossing Threshold");
 
-    // Initialize terminal instance for testing
-    fn setup_terminal() -> WalletTerminal {
-        initialize("test_terminal");
-        WalletTerminal::new()
-    }
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_10", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_11", 500),
+        Transaction::new(TransactionType::Deposit, "wallet_10", 40),
+        Transaction::new(TransactionType::Deposit, "wallet_10", 30),
+    ];
 
-    #[test]
-    fn test_terminal_creation() {
-        // Test terminal initialization
-        let terminal = setup_terminal();
-        let result = calculate_wallet_balance(&[], "test_wallet").unwrap();
-        assert_eq!(result, 0);
-    }
+    let index = transaction_crossing_threshold(&transactions, "wallet_10", 150).unwrap();
+    assert_eq!(index, Some(3));
 
-    // Helper function to process test transactions
-    fn execute_transactions(transactions: Vec<Transaction>) -> Result<i64, WalletError> {
-        let wallet_address = &transactions[0].wallet_address;
-        calculate_wallet_balance(&transactions, wallet_address)
-    }
+    // A threshold that is never reached yields None
+    let index = transaction_crossing_threshold(&transactions, "wallet_10", 1000).unwrap();
+    assert_eq!(index, None);
 
-    #[test]
-    fn test_terminal_operations() {
-        // Test basic terminal transaction operations
-        let wallet = "test_wallet";
-        let deposit_amount = 100;
-        let withdrawal_amount = 30;
+    log_section_header("End Test: Transaction Crossing Threshold");
+}
 
-        let transactions = vec![
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: wallet.to_string(),
-                amount: deposit_amount,
-            },
-            Transaction {
-                transaction_type: TransactionType::Withdrawal,
-                wallet_address: wallet.to_string(),
-                amount: withdrawal_amount,
-            },
-        ];
+#[test]
+fn test_time_weighted_average_balance() {
+    // Test averaging balances by how long each one was held
+    initialize("test_time_weighted_average_balance");
+    log_section_header("Start Test: Time Weighted Average Balance");
 
-        let final_balance = execute_transactions(transactions).unwrap();
-        assert_eq!(final_balance, deposit_amount - withdrawal_amount);
-    }
+    let start = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_12", 100, start),
+        timestamped(TransactionType::Deposit, "wallet_12", 100, start + Duration::hours(1)),
+        timestamped(TransactionType::Withdrawal, "wallet_12", 50, start + Duration::hours(4)),
+    ];
 
-    #[test]
-    fn test_terminal_insufficient_funds() {
-        // Test terminal handling of insufficient funds
-        let wallet = "test_wallet";
-        
-        let transactions = vec![
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: wallet.to_string(),
-                amount: 50,
-            },
-            Transaction {
-                transaction_type: TransactionType::Withdrawal,
-                wallet_address: wallet.to_string(),
-                amount: 100,
-            },
-        ];
+    // 100 held for 1 hour, then 200 held for 3 hours
+    let average = time_weighted_average_balance(&transactions, "wallet_12").unwrap();
+    assert_eq!(average, Some(175.0));
 
-        let result = execute_transactions(transactions);
-        assert!(matches!(
-            result,
-            Err(WalletError::InsufficientFunds {
-                requested: 100,
-                available: 50
-            })
-        ));
-    }
+    // A single transaction has no duration to weight
+    let average = time_weighted_average_balance(&transactions[..1], "wallet_12").unwrap();
+    assert_eq!(average, None);
 
-    #[test]
-    fn test_terminal_multiple_wallets() {
-        // Test terminal handling of multiple wallet operations
-        let transactions = vec![
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: "wallet1".to_string(),
-                amount: 100,
-            },
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: "wallet2".to_string(),
-                amount: 200,
-            },
-        ];
+    log_section_header("End Test: Time Weighted Average Balance");
+}
 
-        let balance1 = calculate_wallet_balance(&transactions, "wallet1").unwrap();
-        let balance2 = calculate_wallet_balance(&transactions, "wallet2").unwrap();
+#[test]
+fn test_balance_of_type() {
+    // Test summing only one type of transaction over a mixed history
+    initialize("test_balance_of_type");
+    log_section_header("Start Test: Balance Of Type");
 
-        assert_eq!(balance1, 100);
-        assert_eq!(balance2, 200);
-    }
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_13", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_13", 30),
+        Transaction::new(TransactionType::Deposit, "wallet_13", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_14", 75),
+        Transaction::new(TransactionType::Transfer { to: "wallet_14".to_string() }, "wallet_13", 20),
+        Transaction::new(TransactionType::Transfer { to: "wallet_15".to_string() }, "wallet_13", 10),
+    ];
 
-    #[test]
-    fn test_terminal_transaction_history() {
-        // Test terminal transaction history functionality
-        let wallet = "history_wallet";
-        
-        let transactions = vec![
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: wallet.to_string(),
-                amount: 100,
-            },
-            Transaction {
-                transaction_type: TransactionType::Withdrawal,
-                wallet_address: wallet.to_string(),
-                amount: 30,
-            },
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: wallet.to_string(),
-                amount: 50,
-            },
-        ];
+    assert_eq!(balance_of_type(&transactions, "wallet_13", &TransactionType::Deposit), 150);
+    assert_eq!(balance_of_type(&transactions, "wallet_13", &TransactionType::Withdrawal), 30);
 
-        let balance = calculate_wallet_balance(&transactions, wallet).unwrap();
-        assert_eq!(balance, 120);
+    // Any transfer type matches every transfer, whatever its destination
+    let any_transfer = TransactionType::Transfer { to: String::new() };
+    assert_eq!(balance_of_type(&transactions, "wallet_13", &any_transfer), 30);
 
-        // Verify transaction display format
-        let tx = &transactions[0];
-        assert_eq!(
-            format!("{}", tx),
-            "Deposit of 100 to history_wallet"
-        );
-    }
-} 
\ No newline at end of file
+    log_section_header("End Test: Balance Of Type");
+}
+
+#[test]
+fn test_total_fee_revenue() {
+    // Test summing fees across all wallets
+    initialize("test_total_fee_revenue");
+    log_section_header("Start Test: Total Fee Revenue");
+
+    let transactions = vec![
+        Transaction {
+            fee: 2,
+            ..Transaction::new(TransactionType::Deposit, "wallet_15", 100)
+        },
+        Transaction {
+            fee: 3,
+            ..Transaction::new(TransactionType::Withdrawal, "wallet_15", 40)
+        },
+        Transaction {
+            fee: 5,
+            ..Transaction::new(TransactionType::Deposit, "wallet_16", 200)
+        },
+        Transaction::new(TransactionType::Deposit, "wallet_16", 10),
+    ];
+
+    assert_eq!(total_fee_revenue(&transactions), 10);
+    assert_eq!(total_fee_revenue(&[]), 0);
+
+    // Fees from different wallets can add up past i64::MAX
+    let huge_fees = vec![
+        Transaction {
+            fee: i64::MAX,
+            ..Transaction::new(TransactionType::Deposit, "wallet_15", i64::MAX)
+        },
+        Transaction {
+            fee: 1,
+            ..Transaction::new(TransactionType::Deposit, "wallet_16", 1)
+        },
+    ];
+    assert_eq!(total_fee_revenue(&huge_fees), i64::MAX);
+
+    log_section_header("End Test: Total Fee Revenue");
+}
+
+#[test]
+fn test_inbound_from() {
+    // Test totalling transfers received from a specific source wallet
+    initialize("test_inbound_from");
+    log_section_header("Start Test: Inbound From");
+
+    let transfer_to_b = || TransactionType::Transfer {
+        to: String::from("wallet_b"),
+    };
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_a", 200),
+        Transaction::new(transfer_to_b(), "wallet_a", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_c", 100),
+        Transaction::new(transfer_to_b(), "wallet_c", 25),
+        Transaction::new(transfer_to_b(), "wallet_a", 70),
+    ];
+
+    assert_eq!(inbound_from(&transactions, "wallet_b", "wallet_a"), 120);
+    assert_eq!(inbound_from(&transactions, "wallet_b", "wallet_c"), 25);
+
+    // The same funds sent back and forth can total more than i64::MAX
+    let round_trips = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_a", i64::MAX),
+        Transaction::new(transfer_to_b(), "wallet_a", i64::MAX),
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_a") }, "wallet_b", i64::MAX),
+        Transaction::new(transfer_to_b(), "wallet_a", i64::MAX),
+    ];
+    assert!(validate_history(&round_trips).is_ok());
+    assert_eq!(inbound_from(&round_trips, "wallet_b", "wallet_a"), i64::MAX);
+
+    // Both legs of each transfer are reflected in the balances
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_a").unwrap(), 80);
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_b").unwrap(), 145);
+
+    log_section_header("End Test: Inbound From");
+}
+
+#[test]
+fn test_balance_from_iter() {
+    // Test balance calculation over a lazily produced transaction stream
+    initialize("test_balance_from_iter");
+    log_section_header("Start Test: Balance From Iterator");
+
+    let transactions = std::iter::once(Transaction::new(TransactionType::Deposit, "wallet_17", 100))
+        .chain(std::iter::once(Transaction::new(TransactionType::Deposit, "wallet_18", 500)))
+        .chain((1..=3).map(|amount| Transaction::new(TransactionType::Withdrawal, "wallet_17", amount * 10)));
+
+    let balance = balance_from_iter(transactions, "wallet_17").unwrap();
+    assert_eq!(balance, 40);
+
+    // Validation still applies to streamed transactions
+    let overdraft = std::iter::once(Transaction::new(TransactionType::Withdrawal, "wallet_17", 10));
+    assert!(matches!(
+        balance_from_iter(overdraft, "wallet_17"),
+        Err(WalletError::InsufficientFunds { .. })
+    ));
+
+    log_section_header("End Test: Balance From Iterator");
+}
+
+#[test]
+fn test_find_wash_pairs() {
+    // Test detection of back-to-back offsetting deposit/withdrawal pairs
+    initialize("test_find_wash_pairs");
+    log_section_header("Start Test: Find Wash Pairs");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_19", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_19", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_20", 10),
+        Transaction::new(TransactionType::Withdrawal, "wallet_19", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_19", 20),
+        Transaction::new(TransactionType::Deposit, "wallet_19", 30),
+    ];
+
+    assert_eq!(find_wash_pairs(&transactions, "wallet_19"), vec![(1, 3)]);
+    assert!(find_wash_pairs(&transactions, "wallet_20").is_empty());
+
+    log_section_header("End Test: Find Wash Pairs");
+}
+
+#[test]
+fn test_balance_with_anniversary_interest() {
+    // Test yearly compounding of interest on a deposit
+    initialize("test_balance_with_anniversary_interest");
+    log_section_header("Start Test: Balance With Anniversary Interest");
+
+    let as_of = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_21", 1000, as_of - Duration::days(366)),
+        timestamped(TransactionType::Deposit, "wallet_21", 500, as_of - Duration::days(30)),
+    ];
+
+    // Only the year-old deposit has reached an anniversary
+    let balance = balance_with_anniversary_interest(&transactions, "wallet_21", 1000, as_of).unwrap();
+    assert_eq!(balance, 1100 + 500);
+
+    // Withdrawn funds stop earning interest
+    let drained = vec![
+        timestamped(TransactionType::Deposit, "wallet_21a", 100, as_of - Duration::days(366)),
+        timestamped(TransactionType::Withdrawal, "wallet_21a", 100, as_of - Duration::days(300)),
+    ];
+    assert_eq!(balance_with_anniversary_interest(&drained, "wallet_21a", 1000, as_of).unwrap(), 0);
+
+    // A withdrawal consumes the oldest deposit first, leaving the newer one untouched
+    let partly_drained = vec![
+        timestamped(TransactionType::Deposit, "wallet_21b", 1000, as_of - Duration::days(800)),
+        timestamped(TransactionType::Deposit, "wallet_21b", 1000, as_of - Duration::days(400)),
+        timestamped(TransactionType::Withdrawal, "wallet_21b", 600, as_of - Duration::days(380)),
+    ];
+    // 400 left of the first lot for two years, 1000 of the second for one year
+    let balance = balance_with_anniversary_interest(&partly_drained, "wallet_21b", 1000, as_of).unwrap();
+    assert_eq!(balance, 484 + 1100);
+
+    log_section_header("End Test: Balance With Anniversary Interest");
+}
+
+#[test]
+fn test_balance_correlation() {
+    // Test correlation of two wallets whose balances move in lockstep
+    initialize("test_balance_correlation");
+    log_section_header("Start Test: Balance Correlation");
+
+    let start = base_time();
+    let later = start + Duration::hours(1);
+    let latest = start + Duration::hours(2);
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_22", 100, start),
+        timestamped(TransactionType::Deposit, "wallet_23", 200, start),
+        timestamped(TransactionType::Deposit, "wallet_22", 100, later),
+        timestamped(TransactionType::Deposit, "wallet_99", 500, later),
+        timestamped(TransactionType::Deposit, "wallet_23", 200, later),
+        timestamped(TransactionType::Withdrawal, "wallet_22", 50, latest),
+        timestamped(TransactionType::Withdrawal, "wallet_23", 100, latest),
+    ];
+
+    // wallet_23 always holds exactly twice wallet_22's balance
+    let correlation = balance_correlation(&transactions, "wallet_22", "wallet_23")
+        .unwrap()
+        .unwrap();
+    assert!((correlation - 1.0).abs() < 1e-9, "expected 1.0, got {}", correlation);
+
+    // Too little data yields None
+    assert_eq!(balance_correlation(&[], "wallet_22", "wallet_23").unwrap(), None);
+
+    log_section_header("End Test: Balance Correlation");
+}
+
+#[test]
+fn test_validate_daily_withdrawal_limit() {
+    // Test flagging calendar days whose withdrawals exceed the limit
+    initialize("test_validate_daily_withdrawal_limit");
+    log_section_header("Start Test: Validate Daily Withdrawal Limit");
+
+    let day_one = base_time();
+    let day_two = day_one + Duration::days(1);
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_26", 1000, day_one),
+        timestamped(TransactionType::Withdrawal, "wallet_26", 60, day_one),
+        timestamped(TransactionType::Withdrawal, "wallet_26", 40, day_one + Duration::hours(1)),
+        timestamped(TransactionType::Withdrawal, "wallet_26", 80, day_two),
+        timestamped(TransactionType::Withdrawal, "wallet_26", 30, day_two + Duration::hours(2)),
+    ];
+
+    assert_eq!(
+        validate_daily_withdrawal_limit(&transactions, "wallet_26", 100),
+        Err(vec![day_two.date_naive()])
+    );
+    assert_eq!(validate_daily_withdrawal_limit(&transactions, "wallet_26", 110), Ok(()));
+
+    // Outgoing transfers and fees count towards the day's total; incoming transfers do not
+    let mut transfer_out = timestamped(
+        TransactionType::Transfer { to: "wallet_26b".to_string() },
+        "wallet_26",
+        50,
+        day_one,
+    );
+    transfer_out.fee = 5;
+    let transfer_in = timestamped(
+        TransactionType::Transfer { to: "wallet_26".to_string() },
+        "wallet_26b",
+        500,
+        day_two,
+    );
+    let mut with_transfers = transactions.clone();
+    with_transfers.push(transfer_out);
+    with_transfers.push(transfer_in);
+    assert_eq!(
+        validate_daily_withdrawal_limit(&with_transfers, "wallet_26", 150),
+        Err(vec![day_one.date_naive()])
+    );
+    assert_eq!(validate_daily_withdrawal_limit(&with_transfers, "wallet_26", 155), Ok(()));
+
+    log_section_header("End Test: Validate Daily Withdrawal Limit");
+}
+
+#[test]
+fn test_counterparty_count() {
+    // Test counting distinct transfer counterparties
+    initialize("test_counterparty_count");
+    log_section_header("Start Test: Counterparty Count");
+
+    let transfer_to = |to: &str| TransactionType::Transfer { to: to.to_string() };
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_27", 300),
+        Transaction::new(transfer_to("wallet_28"), "wallet_27", 50),
+        Transaction::new(transfer_to("wallet_29"), "wallet_27", 50),
+        Transaction::new(transfer_to("wallet_28"), "wallet_27", 25),
+        Transaction::new(transfer_to("wallet_27"), "wallet_28", 10),
+    ];
+
+    assert_eq!(counterparty_count(&transactions, "wallet_27"), 2);
+    assert_eq!(counterparty_count(&transactions, "wallet_29"), 1);
+    assert_eq!(counterparty_count(&transactions, "wallet_30"), 0);
+
+    log_section_header("End Test: Counterparty Count");
+}
+
+#[test]
+fn test_monthly_statement() {
+    // Test statement generation across a month boundary
+    initialize("test_monthly_statement");
+    log_section_header("Start Test: Monthly Statement");
+
+    let january = Local.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+    let february = Local.with_ymd_and_hms(2024, 2, 3, 9, 0, 0).unwrap();
+    let march = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_31", 200, january),
+        timestamped(TransactionType::Withdrawal, "wallet_31", 50, january),
+        timestamped(TransactionType::Deposit, "wallet_31", 100, february),
+        timestamped(TransactionType::Withdrawal, "wallet_31", 20, february + Duration::days(5)),
+        timestamped(TransactionType::Withdrawal, "wallet_31", 100, march),
+    ];
+
+    let statement = monthly_statement(&transactions, "wallet_31", 2024, 2).unwrap();
+    let lines: Vec<&str> = statement.lines().collect();
+
+    assert_eq!(lines[0], "Statement for wallet wallet_31 - 2024-02");
+    assert_eq!(lines[1], "Opening balance: 1.50");
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[2],
+        "2024-02-03 09:00:00 | Deposit of 1.00 to wallet_31 | Running balance: 2.50"
+    );
+    assert_eq!(lines[4], "Closing balance: 2.30");
+
+    log_section_header("End Test: Monthly Statement");
+}
+
+#[test]
+fn test_required_rate_bps() {
+    // Test the rate needed to double a balance over several periods
+    initialize("test_required_rate_bps");
+    log_section_header("Start Test: Required Rate BPS");
+
+    // Doubling over 10 periods needs roughly 7.18% per period
+    let rate = required_rate_bps(1000, 2000, 10).unwrap();
+    assert!((715..=720).contains(&rate), "unexpected rate {}", rate);
+    assert_eq!(required_rate_bps(1000, 2000, 1), Some(10_000));
+    assert_eq!(required_rate_bps(1000, 1000, 5), Some(0));
+
+    // Impossible goals yield None
+    assert_eq!(required_rate_bps(1000, 500, 5), None);
+    assert_eq!(required_rate_bps(0, 500, 5), None);
+    assert_eq!(required_rate_bps(1000, 2000, 0), None);
+
+    log_section_header("End Test: Required Rate BPS");
+}
+
+#[test]
+fn test_transactions_to_goal() {
+    // Test counting recurring deposits needed to reach a goal
+    initialize("test_transactions_to_goal");
+    log_section_header("Start Test: Transactions To Goal");
+
+    assert_eq!(transactions_to_goal(50, 100, 300), Some(4));
+    assert_eq!(transactions_to_goal(60, 100, 300), Some(4));
+    assert_eq!(transactions_to_goal(50, 300, 300), Some(0));
+    assert_eq!(transactions_to_goal(50, 400, 300), Some(0));
+    assert_eq!(transactions_to_goal(0, 100, 300), None);
+    assert_eq!(transactions_to_goal(-10, 100, 300), None);
+
+    log_section_header("End Test: Transactions To Goal");
+}
+
+#[test]
+fn test_wallets_by_balance() {
+    // Test ranking wallets by balance with ties broken by address
+    initialize("test_wallets_by_balance");
+    log_section_header("Start Test: Wallets By Balance");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_b", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_c", 300),
+        Transaction::new(TransactionType::Deposit, "wallet_a", 100),
+        Transaction::new(
+            TransactionType::Transfer {
+                to: String::from("wallet_d"),
+            },
+            "wallet_c",
+            50,
+        ),
+    ];
+
+    assert_eq!(
+        wallets_by_balance(&transactions).unwrap(),
+        vec![
+            (String::from("wallet_c"), 250),
+            (String::from("wallet_a"), 100),
+            (String::from("wallet_b"), 100),
+            (String::from("wallet_d"), 50),
+        ]
+    );
+
+    log_section_header("End Test: Wallets By Balance");
+}
+
+#[test]
+fn test_balance_if_reversed() {
+    // Test reversing a withdrawal raises the resulting balance
+    initialize("test_balance_if_reversed");
+    log_section_header("Start Test: Balance If Reversed");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_32", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_32", 40),
+        Transaction::new(TransactionType::Deposit, "wallet_32", 10),
+    ];
+    assert_ne!(transactions[0].id, transactions[1].id);
+
+    let reversed: std::collections::HashSet<u64> = [transactions[1].id].into_iter().collect();
+    assert_eq!(balance_if_reversed(&transactions, "wallet_32", &reversed).unwrap(), 150);
+
+    // Reversing a deposit the later withdrawal depended on breaks the sequence
+    let reversed: std::collections::HashSet<u64> = [transactions[0].id].into_iter().collect();
+    assert!(matches!(
+        balance_if_reversed(&transactions, "wallet_32", &reversed),
+        Err(WalletError::InsufficientFunds { .. })
+    ));
+
+    log_section_header("End Test: Balance If Reversed");
+}
+
+#[test]
+fn test_busiest_wallet() {
+    // Test finding the wallet with the most transactions
+    initialize("test_busiest_wallet");
+    log_section_header("Start Test: Busiest Wallet");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_34", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_33", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_35", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_34", 10),
+        Transaction::new(TransactionType::Deposit, "wallet_35", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_34", 10),
+    ];
+
+    assert_eq!(busiest_wallet(&transactions), Some((String::from("wallet_34"), 3)));
+    // Ties go to the alphabetically first address
+    assert_eq!(busiest_wallet(&transactions[..5]), Some((String::from("wallet_34"), 2)));
+    assert_eq!(busiest_wallet(&[]), None);
+
+    log_section_header("End Test: Busiest Wallet");
+}
+
+#[test]
+fn test_validate_withdrawal_policies() {
+    // Test that each policy in the stack can reject a withdrawal
+    initialize("test_validate_withdrawal_policies");
+    log_section_header("Start Test: Validate Withdrawal Policies");
+
+    let now = base_time();
+    let transactions = vec![timestamped(TransactionType::Deposit, "wallet_36", 500, now)];
+    let policies = [
+        WithdrawalPolicy::MaxSingle(200),
+        WithdrawalPolicy::SufficientFunds,
+    ];
+
+    assert!(validate_withdrawal(&transactions, "wallet_36", 150, 0, now, &policies).is_ok());
+    assert!(matches!(
+        validate_withdrawal(&transactions, "wallet_36", 300, 0, now, &policies),
+        Err(WalletError::AmountExceedsLimit {
+            amount: 300,
+            limit: 200
+        })
+    ));
+    assert!(matches!(
+        validate_withdrawal(&transactions, "wallet_37", 150, 0, now, &policies),
+        Err(WalletError::InsufficientFunds {
+            requested: 150,
+            available: 0
+        })
+    ));
+
+    // The fee has to be covered as well as the amount
+    let funds = [WithdrawalPolicy::SufficientFunds];
+    assert!(validate_withdrawal(&transactions, "wallet_36", 490, 10, now, &funds).is_ok());
+    assert!(matches!(
+        validate_withdrawal(&transactions, "wallet_36", 490, 11, now, &funds),
+        Err(WalletError::InsufficientFunds {
+            requested: 501,
+            available: 500
+        })
+    ));
+    assert!(matches!(
+        validate_withdrawal(&transactions, "wallet_36", 10, -1, now, &funds),
+        Err(WalletError::InvalidAmount(-1))
+    ));
+
+    let frozen = [String::from("wallet_36")].into_iter().collect();
+    assert!(matches!(
+        validate_withdrawal(&transactions, "wallet_36", 10, 0, now, &[WithdrawalPolicy::NotFrozen(frozen)]),
+        Err(WalletError::WalletFrozen(_))
+    ));
+
+    // The daily limit covers the as_of date, and huge totals are rejected instead of wrapping
+    let history = vec![
+        timestamped(TransactionType::Deposit, "wallet_36", i64::MAX, now - Duration::days(1)),
+        timestamped(TransactionType::Withdrawal, "wallet_36", i64::MAX - 100, now - Duration::days(1)),
+    ];
+    let daily = [WithdrawalPolicy::DailyLimit(1000)];
+    assert!(validate_withdrawal(&history, "wallet_36", 900, 0, now, &daily).is_ok());
+    assert!(matches!(
+        validate_withdrawal(&history, "wallet_36", 900, 101, now, &daily),
+        Err(WalletError::DailyLimitExceeded { limit: 1000, .. })
+    ));
+    let yesterday = now - Duration::days(1);
+    assert!(matches!(
+        validate_withdrawal(&history, "wallet_36", i64::MAX, 0, yesterday, &daily),
+        Err(WalletError::DailyLimitExceeded { limit: 1000, date }) if date == yesterday.date_naive()
+    ));
+
+    log_section_header("End Test: Validate Withdrawal Policies");
+}
+
+#[test]
+fn test_chain_hashes() {
+    // Test that tampering with an early transaction invalidates every later link
+    initialize("test_chain_hashes");
+    log_section_header("Start Test: Chain Hashes");
+
+    let mut transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_38", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_38", 30),
+        Transaction::new(TransactionType::Deposit, "wallet_39", 70),
+    ];
+
+    let original = chain_hashes(&transactions);
+    assert_eq!(original.len(), 3);
+    assert_eq!(chain_hashes(&transactions), original);
+
+    transactions[0].amount = Money::from_minor(101);
+    let tampered = chain_hashes(&transactions);
+    for (before, after) in original.iter().zip(&tampered) {
+        assert_ne!(before, after);
+    }
+
+    // Idempotency keys are part of each link too
+    transactions[0].amount = Money::from_minor(100);
+    assert_eq!(chain_hashes(&transactions), original);
+    transactions[1].idempotency_key = Some(String::from("chain-key"));
+    let keyed = chain_hashes(&transactions);
+    assert_eq!(keyed[0], original[0]);
+    assert_ne!(keyed[1], original[1]);
+    assert_ne!(keyed[2], original[2]);
+
+    log_section_header("End Test: Chain Hashes");
+}
+
+#[test]
+fn test_balance_with_expiry() {
+    // Test that only unexpired deposited funds count toward the balance
+    initialize("test_balance_with_expiry");
+    log_section_header("Start Test: Balance With Expiry");
+
+    let as_of = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_40", 100, as_of - Duration::days(40)),
+        timestamped(TransactionType::Deposit, "wallet_40", 80, as_of - Duration::days(5)),
+        timestamped(TransactionType::Withdrawal, "wallet_40", 30, as_of - Duration::days(2)),
+    ];
+
+    // The withdrawal consumed the old deposit first, leaving 70 of it to expire
+    let balance = balance_with_expiry(&transactions, "wallet_40", Duration::days(30), as_of).unwrap();
+    assert_eq!(balance, 80);
+
+    // Nothing has expired with a long enough window
+    let balance = balance_with_expiry(&transactions, "wallet_40", Duration::days(60), as_of).unwrap();
+    assert_eq!(balance, 150);
+
+    log_section_header("End Test: Balance With Expiry");
+}
+
+#[test]
+fn test_sustainable_deposit() {
+    // Test the minimum deposit that keeps withdrawals funded
+    initialize("test_sustainable_deposit");
+    log_section_header("Start Test: Sustainable Deposit");
+
+    // 12 periods of 100 need 1200, of which the starting 300 covers a quarter
+    assert_eq!(sustainable_deposit(100, 12, 300), 75);
+    // Rounding up keeps the final balance from dipping below zero
+    assert_eq!(sustainable_deposit(100, 12, 301), 75);
+    assert_eq!(sustainable_deposit(100, 12, 299), 76);
+    assert_eq!(sustainable_deposit(100, 3, 500), 0);
+    assert_eq!(sustainable_deposit(100, 0, 0), 0);
+
+    log_section_header("End Test: Sustainable Deposit");
+}
+
+#[test]
+fn test_summarize_errors() {
+    // Test grouping batch errors by kind
+    initialize("test_summarize_errors");
+    log_section_header("Start Test: Summarize Errors");
+
+    let errors = vec![
+        (0, WalletError::InvalidAmount(-5)),
+        (3, WalletError::InsufficientFunds {
+            requested: 100,
+            available: 20,
+        }),
+        (4, WalletError::InvalidAmount(-1)),
+        (7, WalletError::InsufficientFunds {
+            requested: 10,
+            available: 0,
+        }),
+        (9, WalletError::InvalidAmount(-2)),
+    ];
+
+    let summary = summarize_errors(&errors);
+    assert_eq!(summary.count("InvalidAmount"), 3);
+    assert_eq!(summary.count("InsufficientFunds"), 2);
+    assert_eq!(summary.count("WalletFrozen"), 0);
+    assert_eq!(summary.total(), 5);
+    assert_eq!(summary.by_kind["InvalidAmount"], vec![0, 4, 9]);
+    assert_eq!(summary.by_kind["InsufficientFunds"], vec![3, 7]);
+
+    log_section_header("End Test: Summarize Errors");
+}
+
+#[test]
+fn test_project_with_drawdown() {
+    // Test a drawdown projection until the balance is depleted
+    initialize("test_project_with_drawdown");
+    log_section_header("Start Test: Project With Drawdown");
+
+    // 10% interest then a 400 withdrawal each period
+    let projection = project_with_drawdown(1000, 1000, 400, 5);
+    assert_eq!(projection, vec![700, 370, 7, 0, 0]);
+
+    // Interest alone grows the balance
+    assert_eq!(project_with_drawdown(1000, 500, 0, 2), vec![1050, 1102]);
+
+    log_section_header("End Test: Project With Drawdown");
+}
+
+#[test]
+fn test_balance_with_modified_amount() {
+    // Test what-if balances when a transaction's amount changes
+    initialize("test_balance_with_modified_amount");
+    log_section_header("Start Test: Balance With Modified Amount");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_41", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_41", 60),
+    ];
+    let deposit_id = transactions[0].id;
+
+    assert_eq!(
+        balance_with_modified_amount(&transactions, "wallet_41", deposit_id, 250).unwrap(),
+        190
+    );
+    // The original history is untouched
+    assert_eq!(transactions[0].amount, Money::from_minor(100));
+
+    assert!(matches!(
+        balance_with_modified_amount(&transactions, "wallet_41", deposit_id, 50),
+        Err(WalletError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(
+        balance_with_modified_amount(&transactions, "wallet_41", deposit_id, -5),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+    assert!(matches!(
+        balance_with_modified_amount(&transactions, "wallet_41", u64::MAX, 10),
+        Err(WalletError::TransactionNotFound(u64::MAX))
+    ));
+
+    // A transaction of another wallet leaves the balance unchanged, but a negative
+    // amount is still rejected
+    let mut with_foreign = transactions.clone();
+    with_foreign.push(Transaction::new(TransactionType::Deposit, "wallet_41b", 30));
+    let foreign_id = with_foreign[2].id;
+    assert_eq!(
+        balance_with_modified_amount(&with_foreign, "wallet_41", foreign_id, 500).unwrap(),
+        40
+    );
+    assert!(matches!(
+        balance_with_modified_amount(&with_foreign, "wallet_41", foreign_id, -5),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Balance With Modified Amount");
+}
+
+#[test]
+fn test_balance_in_base() {
+    // Test converting per-currency balances into a single base currency
+    initialize("test_balance_in_base");
+    log_section_header("Start Test: Balance In Base");
+
+    let in_currency = |transaction_type, amount, currency: &str| Transaction {
+        currency: currency.to_string(),
+        ..Transaction::new(transaction_type, "wallet_42", amount)
+    };
+    let transactions = vec![
+        in_currency(TransactionType::Deposit, 100, "USD"),
+        in_currency(TransactionType::Deposit, 200, "EUR"),
+        in_currency(TransactionType::Withdrawal, 50, "EUR"),
+    ];
+
+    let balances = balances_by_currency(&transactions, "wallet_42").unwrap();
+    assert_eq!(balances["USD"], 100);
+    assert_eq!(balances["EUR"], 150);
+
+    let mut rates = std::collections::HashMap::new();
+    rates.insert(String::from("USD"), 1.0);
+    rates.insert(String::from("EUR"), 1.1);
+    let total = balance_in_base(&transactions, "wallet_42", &rates).unwrap();
+    assert!((total - 265.0).abs() < 1e-9);
+
+    rates.remove("EUR");
+    assert!(matches!(
+        balance_in_base(&transactions, "wallet_42", &rates),
+        Err(WalletError::MissingExchangeRate(currency)) if currency == "EUR"
+    ));
+
+    log_section_header("End Test: Balance In Base");
+}
+
+#[test]
+fn test_amount_outliers() {
+    // Test z-score detection of an unusually large transaction
+    initialize("test_amount_outliers");
+    log_section_header("Start Test: Amount Outliers");
+
+    let mut transactions: Vec<Transaction> = [100, 110, 90, 105, 95, 100, 5000, 100]
+        .iter()
+        .map(|amount| Transaction::new(TransactionType::Deposit, "wallet_43", *amount))
+        .collect();
+    transactions.insert(2, Transaction::new(TransactionType::Deposit, "wallet_44", 9999));
+
+    assert_eq!(amount_outliers(&transactions, "wallet_43", 2.0).unwrap(), vec![7]);
+
+    // Zero variance never produces outliers
+    let flat: Vec<Transaction> = (0..3)
+        .map(|_| Transaction::new(TransactionType::Deposit, "wallet_45", 50))
+        .collect();
+    assert!(amount_outliers(&flat, "wallet_45", 0.5).unwrap().is_empty());
+
+    log_section_header("End Test: Amount Outliers");
+}
+
+#[test]
+fn test_settlement_report() {
+    // Test separating confirmed funds from pending inflows and outflows
+    initialize("test_settlement_report");
+    log_section_header("Start Test: Settlement Report");
+
+    let pending = |transaction_type, amount| Transaction {
+        status: TransactionStatus::Pending,
+        ..Transaction::new(transaction_type, "wallet_46", amount)
+    };
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_46", 200),
+        Transaction::new(TransactionType::Withdrawal, "wallet_46", 50),
+        pending(TransactionType::Deposit, 80),
+        pending(TransactionType::Withdrawal, 30),
+        pending(TransactionType::Withdrawal, 20),
+    ];
+
+    let report = settlement_report(&transactions, "wallet_46").unwrap();
+    assert_eq!(
+        report,
+        SettlementReport {
+            confirmed_balance: 150,
+            pending_inflow: 80,
+            pending_outflow: 50,
+            projected_balance: 180,
+        }
+    );
+
+    log_section_header("End Test: Settlement Report");
+}
+
+#[test]
+fn test_largest_withdrawal() {
+    // Test finding the biggest expense with ties favoring the earliest
+    initialize("test_largest_withdrawal");
+    log_section_header("Start Test: Largest Withdrawal");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_47", 1000),
+        Transaction::new(TransactionType::Withdrawal, "wallet_47", 120),
+        Transaction::new(TransactionType::Withdrawal, "wallet_47", 300),
+        Transaction::new(TransactionType::Withdrawal, "wallet_48", 900),
+        Transaction::new(TransactionType::Withdrawal, "wallet_47", 300),
+        Transaction::new(TransactionType::Withdrawal, "wallet_47", 45),
+    ];
+
+    let largest = largest_withdrawal(&transactions, "wallet_47").unwrap();
+    assert_eq!(largest, &transactions[2]);
+    assert!(largest_withdrawal(&transactions[..1], "wallet_47").is_none());
+
+    log_section_header("End Test: Largest Withdrawal");
+}
+
+#[test]
+fn test_window_parity() {
+    // Test totaling deposits and withdrawals with inclusive window bounds
+    initialize("test_window_parity");
+    log_section_header("Start Test: Window Parity");
+
+    let start = base_time();
+    let end = start + Duration::days(7);
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_49", 999, start - Duration::seconds(1)),
+        timestamped(TransactionType::Deposit, "wallet_49", 500, start),
+        timestamped(TransactionType::Withdrawal, "wallet_49", 120, start + Duration::days(2)),
+        timestamped(TransactionType::Deposit, "wallet_50", 700, start + Duration::days(3)),
+        timestamped(
+            TransactionType::Transfer { to: String::from("wallet_50") },
+            "wallet_49",
+            50,
+            start + Duration::days(4),
+        ),
+        timestamped(TransactionType::Withdrawal, "wallet_49", 80, end),
+        timestamped(TransactionType::Withdrawal, "wallet_49", 999, end + Duration::seconds(1)),
+    ];
+
+    assert_eq!(window_parity(&transactions, "wallet_49", start, end), (500, 200));
+    assert_eq!(window_parity(&transactions, "wallet_49", end, end), (0, 80));
+    assert_eq!(window_parity(&transactions, "wallet_51", start, end), (0, 0));
+
+    log_section_header("End Test: Window Parity");
+}
+
+#[test]
+fn test_schedule_variance() {
+    // Test that a shortfall against the deposit schedule is a positive variance
+    initialize("test_schedule_variance");
+    log_section_header("Start Test: Schedule Variance");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_54", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_54", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_54", 30),
+        Transaction::new(TransactionType::Deposit, "wallet_54", 60),
+        Transaction::new(TransactionType::Deposit, "wallet_55", 500),
+    ];
+
+    assert_eq!(schedule_variance(&transactions, "wallet_54", 100, 3).unwrap(), 40);
+    assert_eq!(schedule_variance(&transactions, "wallet_54", 100, 2).unwrap(), -60);
+    assert!(matches!(
+        schedule_variance(&transactions, "wallet_54", -100, 3),
+        Err(WalletError::InvalidAmount(-100))
+    ));
+
+    // Totals that do not fit in an i64 are reported instead of wrapping
+    assert!(matches!(
+        schedule_variance(&transactions, "wallet_54", i64::MAX, 2),
+        Err(WalletError::BalanceOverflow { ref wallet }) if wallet == "wallet_54"
+    ));
+    let huge = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_54b", i64::MAX),
+        Transaction::new(TransactionType::Deposit, "wallet_54b", 1),
+    ];
+    assert!(matches!(
+        schedule_variance(&huge, "wallet_54b", 0, 1),
+        Err(WalletError::BalanceOverflow { .. })
+    ));
+
+    log_section_header("End Test: Schedule Variance");
+}
+
+#[test]
+fn test_missing_periods() {
+    // Test that a skipped payment in a weekly schedule is reported by index
+    initialize("test_missing_periods");
+    log_section_header("Start Test: Missing Periods");
+
+    let start = base_time();
+    let week = Duration::days(7);
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_56", 50, start),
+        timestamped(TransactionType::Deposit, "wallet_56", 50, start + week + Duration::hours(6)),
+        timestamped(TransactionType::Deposit, "wallet_57", 50, start + week * 2),
+        timestamped(TransactionType::Deposit, "wallet_56", 50, start + week * 3 - Duration::days(1)),
+    ];
+
+    assert_eq!(missing_periods(&transactions, "wallet_56", start, week, 4), vec![2]);
+    assert_eq!(missing_periods(&transactions, "wallet_56", start, week, 5), vec![2, 4]);
+    assert_eq!(missing_periods(&transactions, "wallet_57", start, week, 3), vec![0, 1]);
+
+    // Periods whose due time cannot be represented are missing rather than wrapping
+    let ages = Duration::days(365 * 100_000);
+    assert_eq!(missing_periods(&transactions, "wallet_56", start, ages, 4), vec![1, 2, 3]);
+
+    log_section_header("End Test: Missing Periods");
+}
+
+#[test]
+fn test_verify_final_balance() {
+    // Test checking claimed balances against the replayed history
+    initialize("test_verify_final_balance");
+    log_section_header("Start Test: Verify Final Balance");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_58", 300),
+        Transaction::new(TransactionType::Withdrawal, "wallet_58", 120),
+    ];
+
+    assert!(verify_final_balance(&transactions, "wallet_58", 180).unwrap());
+    assert!(!verify_final_balance(&transactions, "wallet_58", 300).unwrap());
+
+    let overdrawn = vec![Transaction::new(TransactionType::Withdrawal, "wallet_58", 10)];
+    assert!(matches!(
+        verify_final_balance(&overdrawn, "wallet_58", -10),
+        Err(WalletError::InsufficientFunds { .. })
+    ));
+
+    log_section_header("End Test: Verify Final Balance");
+}
+
+#[test]
+fn test_cumulative_wallet_count() {
+    // Test that the count grows exactly when a new wallet first appears
+    initialize("test_cumulative_wallet_count");
+    log_section_header("Start Test: Cumulative Wallet Count");
+
+    let start = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_59", 100, start),
+        timestamped(TransactionType::Deposit, "wallet_60", 100, start + Duration::days(2)),
+        timestamped(TransactionType::Withdrawal, "wallet_59", 20, start + Duration::days(1)),
+        timestamped(
+            TransactionType::Transfer { to: String::from("wallet_61") },
+            "wallet_60",
+            10,
+            start + Duration::days(3),
+        ),
+        timestamped(TransactionType::Deposit, "wallet_59", 5, start + Duration::days(4)),
+    ];
+
+    assert_eq!(
+        cumulative_wallet_count(&transactions),
+        vec![
+            (start, 1),
+            (start + Duration::days(1), 1),
+            (start + Duration::days(2), 2),
+            (start + Duration::days(3), 3),
+            (start + Duration::days(4), 3),
+        ]
+    );
+    assert!(cumulative_wallet_count(&[]).is_empty());
+
+    log_section_header("End Test: Cumulative Wallet Count");
+}
+
+#[test]
+fn test_balance_change_for_tag() {
+    // Test netting only the transactions that carry the requested memo
+    initialize("test_balance_change_for_tag");
+    log_section_header("Start Test: Balance Change For Tag");
+
+    let tagged = |transaction_type, wallet, amount, tag: &str| Transaction {
+        memo: Some(String::from(tag)),
+        ..Transaction::new(transaction_type, wallet, amount)
+    };
+    let transactions = vec![
+        tagged(TransactionType::Deposit, "wallet_62", 3000, "salary"),
+        tagged(TransactionType::Withdrawal, "wallet_62", 1200, "rent"),
+        Transaction::new(TransactionType::Withdrawal, "wallet_62", 40),
+        tagged(TransactionType::Withdrawal, "wallet_62", 1200, "rent"),
+        tagged(TransactionType::Deposit, "wallet_62", 100, "rent"),
+        tagged(TransactionType::Withdrawal, "wallet_63", 900, "rent"),
+    ];
+
+    assert_eq!(balance_change_for_tag(&transactions, "wallet_62", "rent").unwrap(), -2300);
+    assert_eq!(balance_change_for_tag(&transactions, "wallet_62", "salary").unwrap(), 3000);
+    assert_eq!(balance_change_for_tag(&transactions, "wallet_62", "groceries").unwrap(), 0);
+
+    // Large tagged inflows are reported as an overflow instead of wrapping
+    let huge = vec![
+        tagged(TransactionType::Deposit, "wallet_62", i64::MAX, "bonus"),
+        tagged(TransactionType::Deposit, "wallet_62", 1, "bonus"),
+    ];
+    assert!(matches!(
+        balance_change_for_tag(&huge, "wallet_62", "bonus"),
+        Err(WalletError::BalanceOverflow { ref wallet }) if wallet == "wallet_62"
+    ));
+
+    log_section_header("End Test: Balance Change For Tag");
+}
+
+#[test]
+fn test_required_deposits() {
+    // Test that a deposit is only required once the starting balance runs out
+    initialize("test_required_deposits");
+    log_section_header("Start Test: Required Deposits");
+
+    let first = base_time();
+    let second = first + Duration::days(14);
+    let withdrawals = vec![(second, 250), (first, 80)];
+
+    assert_eq!(required_deposits(&withdrawals, 100).unwrap(), vec![(second, 230)]);
+    assert_eq!(required_deposits(&withdrawals, 0).unwrap(), vec![(first, 80), (second, 250)]);
+    assert!(required_deposits(&withdrawals, 330).unwrap().is_empty());
+
+    // Negative balances and amounts are rejected rather than planned around
+    assert!(matches!(
+        required_deposits(&withdrawals, i64::MIN),
+        Err(WalletError::InvalidAmount(i64::MIN))
+    ));
+    assert!(matches!(
+        required_deposits(&[(first, 80), (second, -5)], 100),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Required Deposits");
+}
+
+#[test]
+fn test_wallet_one_liner() {
+    // Test the compact dashboard summary for a known history
+    initialize("test_wallet_one_liner");
+    log_section_header("Start Test: Wallet One Liner");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_64", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_65", 500),
+        Transaction::new(TransactionType::Withdrawal, "wallet_64", 30),
+    ];
+
+    assert_eq!(
+        wallet_one_liner(&transactions, "wallet_64").unwrap(),
+        "wallet_64: bal=70, tx=2, in=100, out=30"
+    );
+
+    let report = wallet_report(&transactions, "wallet_65").unwrap();
+    assert_eq!(report.balance, 500);
+    assert_eq!(report.transaction_count, 1);
+    assert!(wallet_one_liner(&transactions[2..], "wallet_64").is_err());
+
+    log_section_header("End Test: Wallet One Liner");
+}
+
+#[test]
+fn test_withdrawal_ratio() {
+    // Test the share of funds a withdrawal would consume
+    initialize("test_withdrawal_ratio");
+    log_section_header("Start Test: Withdrawal Ratio");
+
+    let transactions = vec![Transaction::new(TransactionType::Deposit, "wallet_66", 100)];
+
+    assert!((withdrawal_ratio(&transactions, "wallet_66", 80).unwrap() - 0.8).abs() < 1e-9);
+    assert!((withdrawal_ratio(&transactions, "wallet_66", 150).unwrap() - 1.5).abs() < 1e-9);
+    assert!(matches!(
+        withdrawal_ratio(&transactions, "wallet_67", 10),
+        Err(WalletError::InsufficientFunds { requested: 10, available: 0 })
+    ));
+    assert!(matches!(
+        withdrawal_ratio(&transactions, "wallet_66", -5),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Withdrawal Ratio");
+}
+
+#[test]
+fn test_calculate_balance_filtered() {
+    // Test balances over predicate-filtered histories with funds checks intact
+    initialize("test_calculate_balance_filtered");
+    log_section_header("Start Test: Calculate Balance Filtered");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_68", 200),
+        Transaction::new(TransactionType::Withdrawal, "wallet_68", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_68", 25),
+        Transaction::new(TransactionType::Deposit, "wallet_69", 999),
+    ];
+
+    let deposits_only = calculate_balance_filtered(&transactions, "wallet_68", |tx| {
+        matches!(tx.transaction_type, TransactionType::Deposit)
+    });
+    assert_eq!(deposits_only.unwrap(), 225);
+    assert_eq!(calculate_balance_filtered(&transactions, "wallet_68", |_| true).unwrap(), 175);
+
+    let small_only = calculate_balance_filtered(&transactions, "wallet_68", |tx| tx.amount < Money::from_minor(100));
+    assert!(matches!(small_only, Err(WalletError::InsufficientFunds { requested: 50, available: 0 })));
+
+    log_section_header("End Test: Calculate Balance Filtered");
+}
+
+#[test]
+fn test_time_since_last_activity() {
+    // Test measuring dormancy from the most recent transaction
+    initialize("test_time_since_last_activity");
+    log_section_header("Start Test: Time Since Last Activity");
+
+    let now = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_70", 100, now - Duration::days(3)),
+        timestamped(TransactionType::Withdrawal, "wallet_70", 10, now - Duration::hours(1)),
+        timestamped(TransactionType::Deposit, "wallet_70", 5, now - Duration::days(1)),
+        timestamped(TransactionType::Deposit, "wallet_71", 5, now),
+    ];
+
+    assert_eq!(
+        time_since_last_activity(&transactions, "wallet_70", now),
+        Some(Duration::hours(1))
+    );
+    assert_eq!(time_since_last_activity(&transactions, "wallet_71", now), Some(Duration::zero()));
+    assert_eq!(time_since_last_activity(&transactions, "wallet_72", now), None);
+
+    log_section_header("End Test: Time Since Last Activity");
+}
+
+#[test]
+fn test_apply_batch_all_or_nothing() {
+    // Test that one bad transaction rejects the whole batch
+    initialize("test_apply_batch_all_or_nothing");
+    log_section_header("Start Test: Apply Batch All Or Nothing");
+
+    let mut existing = vec![Transaction::new(TransactionType::Deposit, "wallet_73", 100)];
+
+    let bad_batch = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_74", 500),
+        Transaction::new(TransactionType::Withdrawal, "wallet_73", 60),
+        Transaction::new(TransactionType::Withdrawal, "wallet_73", 60),
+    ];
+    assert!(matches!(
+        apply_batch(&mut existing, bad_batch),
+        Err(WalletError::InsufficientFunds { requested: 60, available: 40 })
+    ));
+    assert_eq!(existing.len(), 1);
+
+    let clean_batch = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_74", 500),
+        Transaction::new(TransactionType::Withdrawal, "wallet_73", 60),
+        Transaction::new(
+            TransactionType::Transfer { to: String::from("wallet_73") },
+            "wallet_74",
+            200,
+        ),
+    ];
+    apply_batch(&mut existing, clean_batch).unwrap();
+    assert_eq!(existing.len(), 4);
+    assert_eq!(calculate_wallet_balance(&existing, "wallet_73").unwrap(), 240);
+    assert_eq!(calculate_wallet_balance(&existing, "wallet_74").unwrap(), 300);
+
+    log_section_header("End Test: Apply Batch All Or Nothing");
+}
+
+#[test]
+fn test_balance_with_reorder() {
+    // Test that settling a deposit earlier clears an out-of-order overdraft
+    initialize("test_balance_with_reorder");
+    log_section_header("Start Test: Balance With Reorder");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_75", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_75", 120),
+        Transaction::new(TransactionType::Deposit, "wallet_75", 100),
+    ];
+    assert!(calculate_wallet_balance(&transactions, "wallet_75").is_err());
+
+    let late_deposit = transactions[2].id;
+    assert_eq!(balance_with_reorder(&transactions, "wallet_75", late_deposit, 1).unwrap(), 30);
+    assert_eq!(transactions[2].id, late_deposit);
+
+    let withdrawal = transactions[1].id;
+    assert_eq!(balance_with_reorder(&transactions, "wallet_75", withdrawal, 99).unwrap(), 30);
+    assert!(matches!(
+        balance_with_reorder(&transactions, "wallet_75", late_deposit, 2),
+        Err(WalletError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(
+        balance_with_reorder(&transactions, "wallet_75", u64::MAX, 0),
+        Err(WalletError::TransactionNotFound(u64::MAX))
+    ));
+
+    log_section_header("End Test: Balance With Reorder");
+}
+
+#[test]
+fn test_balance_overflow_detected() {
+    // Test that deposits past i64::MAX error instead of wrapping
+    initialize("test_balance_overflow_detected");
+    log_section_header("Start Test: Balance Overflow Detected");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_76", i64::MAX),
+        Transaction::new(TransactionType::Deposit, "wallet_76", 1),
+    ];
+
+    let result = calculate_wallet_balance(&transactions, "wallet_76");
+    assert!(matches!(
+        &result,
+        Err(WalletError::BalanceOverflow { wallet }) if wallet == "wallet_76"
+    ));
+    assert_eq!(result.unwrap_err().kind(), "BalanceOverflow");
+    assert_eq!(calculate_wallet_balance(&transactions[..1], "wallet_76").unwrap(), i64::MAX);
+
+    let transfer_in = vec![
+        transactions[0].clone(),
+        Transaction::new(TransactionType::Deposit, "wallet_77", 5),
+        Transaction::new(
+            TransactionType::Transfer { to: String::from("wallet_76") },
+            "wallet_77",
+            5,
+        ),
+    ];
+    assert!(matches!(
+        calculate_wallet_balance(&transfer_in, "wallet_76"),
+        Err(WalletError::BalanceOverflow { .. })
+    ));
+
+    log_section_header("End Test: Balance Overflow Detected");
+}
+
+#[test]
+fn test_implied_rate_bps() {
+    // Test recovering the compounding rate from observed balances
+    initialize("test_implied_rate_bps");
+    log_section_header("Start Test: Implied Rate Bps");
+
+    assert_eq!(implied_rate_bps(1000, 1210, 2), Some(1000));
+    assert_eq!(implied_rate_bps(1000, 1000, 5), Some(0));
+    assert_eq!(implied_rate_bps(0, 1210, 2), None);
+    assert_eq!(implied_rate_bps(1000, 1210, 0), None);
+    assert_eq!(implied_rate_bps(1000, 900, 1), None);
+
+    log_section_header("End Test: Implied Rate Bps");
+}
+
+#[test]
+fn test_sample_history_is_deterministic_and_valid() {
+    // Test that seeds reproduce the same valid history and differ from each other
+    initialize("test_sample_history_is_deterministic_and_valid");
+    log_section_header("Start Test: Sample History Is Deterministic And Valid");
+
+    let fingerprint = |history: &[Transaction]| -> Vec<String> {
+        history
+            .iter()
+            .map(|tx| format!("{:?}|{}|{}|{}", tx.transaction_type, tx.wallet_address, tx.amount, tx.timestamp))
+            .collect()
+    };
+
+    let first = sample_history(42);
+    let again = sample_history(42);
+    let other = sample_history(7);
+
+    assert!(!first.is_empty());
+    assert_eq!(fingerprint(&first), fingerprint(&again));
+    assert_ne!(fingerprint(&first), fingerprint(&other));
+    assert!(validate_history(&first).is_ok());
+    assert!(validate_history(&other).is_ok());
+    assert!(validate_history(&sample_history(0)).is_ok());
+
+    let overdrawn = vec![Transaction::new(TransactionType::Withdrawal, "sample_1", 5)];
+    assert!(validate_history(&overdrawn).is_err());
+
+    log_section_header("End Test: Sample History Is Deterministic And Valid");
+}
+
+#[test]
+fn test_calculate_all_balances() {
+    // Test single-pass balances for every wallet, including transfer destinations
+    initialize("test_calculate_all_balances");
+    log_section_header("Start Test: Calculate All Balances");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_78", 300),
+        Transaction::new(TransactionType::Deposit, "wallet_79", 50),
+        Transaction::new(
+            TransactionType::Transfer { to: String::from("wallet_80") },
+            "wallet_78",
+            120,
+        ),
+        Transaction::new(TransactionType::Withdrawal, "wallet_79", 20),
+    ];
+
+    let balances = calculate_all_balances(&transactions).unwrap();
+    assert_eq!(balances.len(), 3);
+    assert_eq!(balances["wallet_78"], 180);
+    assert_eq!(balances["wallet_79"], 30);
+    assert_eq!(balances["wallet_80"], 120);
+    for (wallet, balance) in &balances {
+        assert_eq!(calculate_wallet_balance(&transactions, wallet).unwrap(), *balance);
+    }
+
+    let mut overdrawn = transactions.clone();
+    overdrawn.push(Transaction::new(TransactionType::Withdrawal, "wallet_80", 500));
+    match calculate_all_balances(&overdrawn) {
+        Err(WalletError::WalletValidation { wallet, source }) => {
+            assert_eq!(wallet, "wallet_80");
+            assert!(matches!(*source, WalletError::InsufficientFunds { requested: 500, available: 120 }));
+        }
+        other => panic!("expected a wallet validation error, got {:?}", other),
+    }
+
+    let mut invalid = transactions;
+    invalid.push(Transaction::new(TransactionType::Deposit, "wallet_79", -5));
+    assert!(matches!(
+        calculate_all_balances(&invalid),
+        Err(WalletError::WalletValidation { ref wallet, ref source })
+            if wallet == "wallet_79" && matches!(**source, WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Calculate All Balances");
+}
+
+#[test]
+fn test_balance_window() {
+    // Test the opening and closing balances of a window over a wallet's history
+    initialize("test_balance_window");
+    log_section_header("Start Test: Balance Window");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_81", 200),
+        Transaction::new(TransactionType::Withdrawal, "wallet_81", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_81", 30),
+        Transaction::new(TransactionType::Withdrawal, "wallet_81", 100),
+    ];
+
+    assert_eq!(balance_window(&transactions, "wallet_81", 2).unwrap(), (150, 80));
+    assert_eq!(balance_window(&transactions, "wallet_81", 0).unwrap(), (0, 80));
+    assert_eq!(balance_window(&transactions, "wallet_81", 10).unwrap(), (80, 80));
+    assert!(balance_window(&transactions[1..], "wallet_81", 2).is_err());
+
+    log_section_header("End Test: Balance Window");
+}
+
+#[test]
+fn test_remove_zero_amounts() {
+    // Test dropping no-op transactions while keeping the rest in order
+    initialize("test_remove_zero_amounts");
+    log_section_header("Start Test: Remove Zero Amounts");
+
+    let mut transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_82", 0),
+        Transaction::new(TransactionType::Deposit, "wallet_82", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_82", 0),
+        Transaction::new(TransactionType::Withdrawal, "wallet_82", 40),
+    ];
+    let survivors = vec![transactions[1].id, transactions[3].id];
+
+    assert_eq!(remove_zero_amounts(&mut transactions), 2);
+    assert_eq!(transactions.iter().map(|tx| tx.id).collect::<Vec<_>>(), survivors);
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_82").unwrap(), 60);
+    assert_eq!(remove_zero_amounts(&mut transactions), 0);
+
+    log_section_header("End Test: Remove Zero Amounts");
+}
+
+#[test]
+fn test_maximum_balance() {
+    // Test finding the peak balance rather than the final one
+    initialize("test_maximum_balance");
+    log_section_header("Start Test: Maximum Balance");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_83", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_83", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_83", 80),
+        Transaction::new(TransactionType::Deposit, "wallet_83", 30),
+    ];
+
+    assert_eq!(maximum_balance(&transactions, "wallet_83").unwrap(), 150);
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_83").unwrap(), 100);
+    assert_eq!(maximum_balance(&transactions, "wallet_84").unwrap(), 0);
+    assert!(maximum_balance(&transactions[2..], "wallet_83").is_err());
+
+    log_section_header("End Test: Maximum Balance");
+}
+
+#[test]
+fn test_flow_breakdown() {
+    // Test signed net flow per transaction type over a mixed history
+    initialize("test_flow_breakdown");
+    log_section_header("Start Test: Flow Breakdown");
+
+    let transfer = |from: &str, to: &str, amount| {
+        Transaction::new(TransactionType::Transfer { to: to.to_string() }, from, amount)
+    };
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_85", 300),
+        Transaction::new(TransactionType::Deposit, "wallet_86", 200),
+        Transaction::new(TransactionType::Withdrawal, "wallet_85", 40),
+        transfer("wallet_85", "wallet_86", 100),
+        transfer("wallet_86", "wallet_85", 30),
+        Transaction::new(TransactionType::Deposit, "wallet_85", 20),
+        Transaction::new(TransactionType::Withdrawal, "wallet_85", 10),
+    ];
+
+    let flows = flow_breakdown(&transactions, "wallet_85").unwrap();
+    assert_eq!(flows.len(), 3);
+    assert_eq!(flows["Deposit"], 320);
+    assert_eq!(flows["Withdrawal"], -50);
+    assert_eq!(flows["Transfer"], -70);
+    assert_eq!(flows.values().sum::<i64>(), calculate_wallet_balance(&transactions, "wallet_85").unwrap());
+
+    let flows = flow_breakdown(&transactions, "wallet_86").unwrap();
+    assert_eq!(flows["Transfer"], 70);
+    assert!(!flows.contains_key("Withdrawal"));
+
+    log_section_header("End Test: Flow Breakdown");
+}
+
+#[test]
+fn test_activity_span() {
+    // Test the earliest and latest activity regardless of slice order
+    initialize("test_activity_span");
+    log_section_header("Start Test: Activity Span");
+
+    let start = base_time();
+    let transactions = vec![
+        timestamped(TransactionType::Deposit, "wallet_87", 100, start + Duration::days(2)),
+        timestamped(TransactionType::Deposit, "wallet_87", 100, start),
+        timestamped(TransactionType::Deposit, "wallet_88", 100, start - Duration::days(9)),
+        timestamped(TransactionType::Withdrawal, "wallet_87", 50, start + Duration::days(5)),
+    ];
+
+    assert_eq!(
+        activity_span(&transactions, "wallet_87"),
+        Some((start, start + Duration::days(5)))
+    );
+    let single = start - Duration::days(9);
+    assert_eq!(activity_span(&transactions, "wallet_88"), Some((single, single)));
+    assert_eq!(activity_span(&transactions, "wallet_89"), None);
+
+    log_section_header("End Test: Activity Span");
+}
+
+#[test]
+fn test_log_directory_is_per_component() {
+    // Test that components log to their own subdirectory of the configured base
+    initialize("test_log_directory_is_per_component");
+    log_section_header("Start Test: Log Directory Is Per Component");
+
+    let tests_dir = log_directory("tests");
+    let src_dir = log_directory("src");
+    assert!(tests_dir.ends_with("tests"));
+    assert_eq!(tests_dir.parent(), src_dir.parent());
+    assert!(tests_dir.is_dir());
+
+    log_section_header("End Test: Log Directory Is Per Component");
+}
+
+#[test]
+fn test_parse_log_level() {
+    // Test reading log level settings, falling back to info
+    initialize("test_parse_log_level");
+    log_section_header("Start Test: Parse Log Level");
+
+    assert_eq!(parse_log_level(Some("debug")), log::LevelFilter::Debug);
+    assert_eq!(parse_log_level(Some("WARN")), log::LevelFilter::Warn);
+    assert_eq!(parse_log_level(Some(" error ")), log::LevelFilter::Error);
+    assert_eq!(parse_log_level(Some("off")), log::LevelFilter::Off);
+    assert_eq!(parse_log_level(Some("loud")), log::LevelFilter::Info);
+    assert_eq!(parse_log_level(None), log::LevelFilter::Info);
+
+    log_section_header("End Test: Parse Log Level");
+}
+
+#[test]
+fn test_rolling_average_balance() {
+    // Test the moving average of a known running balance series
+    initialize("test_rolling_average_balance");
+    log_section_header("Start Test: Rolling Average Balance");
+
+    // Running balances: 100, 150, 90, 130
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_90", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_90", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_90", 60),
+        Transaction::new(TransactionType::Deposit, "wallet_90", 40),
+    ];
+
+    assert_eq!(
+        rolling_average_balance(&transactions, "wallet_90", 2).unwrap(),
+        vec![125.0, 120.0, 110.0]
+    );
+    assert_eq!(
+        rolling_average_balance(&transactions, "wallet_90", 1).unwrap(),
+        vec![100.0, 150.0, 90.0, 130.0]
+    );
+    assert!(rolling_average_balance(&transactions, "wallet_90", 5).unwrap().is_empty());
+    assert!(matches!(
+        rolling_average_balance(&transactions, "wallet_90", 0),
+        Err(WalletError::InvalidWindow(0))
+    ));
+
+    log_section_header("End Test: Rolling Average Balance");
+}
+
+#[test]
+fn test_init_logging_twice_returns_error() {
+    // Test that a second initialization reports an error instead of panicking
+    initialize("test_init_logging_twice_returns_error");
+    log_section_header("Start Test: Init Logging Twice Returns Error");
+
+    assert!(matches!(
+        init_logging("test_init_logging_twice_returns_error"),
+        Err(fern::InitError::SetLoggerError(_))
+    ));
+
+    log_section_header("End Test: Init Logging Twice Returns Error");
+}
+
+#[test]
+fn test_expected_balance() {
+    // Test the probability-weighted forecast and rejection of bad distributions
+    initialize("test_expected_balance");
+    log_section_header("Start Test: Expected Balance");
+
+    let expected = expected_balance(1000, &[(0.75, 400), (0.25, -200)]).unwrap();
+    assert!((expected - 1250.0).abs() < 1e-9);
+    assert!((expected_balance(50, &[(1.0, 0)]).unwrap() - 50.0).abs() < 1e-9);
+
+    assert!(matches!(
+        expected_balance(1000, &[(0.5, 400), (0.25, -200)]),
+        Err(WalletError::InvalidProbabilities(total)) if (total - 0.75).abs() < 1e-9
+    ));
+    assert!(expected_balance(1000, &[(1.5, 400), (-0.5, -200)]).is_err());
+    assert!(expected_balance(1000, &[]).is_err());
+
+    log_section_header("End Test: Expected Balance");
+}
+
+#[test]
+fn test_validate_wallet_address() {
+    // Test the default address rules and a pluggable stricter policy
+    initialize("test_validate_wallet_address");
+    log_section_header("Start Test: Validate Wallet Address");
+
+    assert!(validate_wallet_address("wallet_1").is_ok());
+    assert!(validate_wallet_address(&"a".repeat(64)).is_ok());
+    for invalid in ["", "wallet 1", "wallet-1", "wallet,1", "wállet"] {
+        assert!(
+            matches!(validate_wallet_address(invalid), Err(WalletError::InvalidAddress(ref addr)) if addr == invalid),
+            "expected '{}' to be rejected",
+            invalid
+        );
+    }
+    assert!(validate_wallet_address(&"a".repeat(65)).is_err());
+
+    let strict = AddressRules {
+        min_length: 8,
+        max_length: 12,
+        extra_chars: vec!['-'],
+    };
+    assert!(strict.validate("wallet-01").is_ok());
+    assert!(strict.validate("wallet_01").is_err());
+    assert!(strict.validate("short").is_err());
+
+    log_section_header("End Test: Validate Wallet Address");
+}
+
+#[test]
+fn test_fees_threshold_index() {
+    // Test finding the transaction where accumulated fees cross a threshold
+    initialize("test_fees_threshold_index");
+    log_section_header("Start Test: Fees Threshold Index");
+
+    let with_fee = |transaction_type, wallet, amount, fee| Transaction {
+        fee,
+        ..Transaction::new(transaction_type, wallet, amount)
+    };
+    let transactions = vec![
+        with_fee(TransactionType::Deposit, "wallet_91", 500, 2),
+        with_fee(TransactionType::Withdrawal, "wallet_92", 10, 50),
+        with_fee(TransactionType::Withdrawal, "wallet_91", 100, 3),
+        with_fee(TransactionType::Deposit, "wallet_91", 0, 0),
+        with_fee(TransactionType::Withdrawal, "wallet_91", 100, 4),
+        with_fee(TransactionType::Withdrawal, "wallet_91", 100, 4),
+    ];
+
+    assert_eq!(fees_threshold_index(&transactions, "wallet_91", 5), Some(4));
+    assert_eq!(fees_threshold_index(&transactions, "wallet_91", 4), Some(2));
+    assert_eq!(fees_threshold_index(&transactions, "wallet_91", 13), None);
+    assert_eq!(fees_threshold_index(&transactions, "wallet_92", 49), Some(1));
+
+    log_section_header("End Test: Fees Threshold Index");
+}
+
+#[test]
+fn test_transaction_history_with_balances() {
+    // Test pairing a wallet's transactions with running balances
+    initialize("test_transaction_history_with_balances");
+    log_section_header("Start Test: Transaction History With Balances");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_93", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_94", 70),
+        Transaction::new(TransactionType::Withdrawal, "wallet_93", 30),
+        Transaction::new(
+            TransactionType::Transfer { to: String::from("wallet_93") },
+            "wallet_94",
+            20,
+        ),
+    ];
+
+    let history = transaction_history_with_balances(&transactions, "wallet_93");
+    let summary: Vec<(u64, i64)> = history.iter().map(|(tx, balance)| (tx.id, *balance)).collect();
+    assert_eq!(
+        summary,
+        vec![(transactions[0].id, 100), (transactions[2].id, 70), (transactions[3].id, 90)]
+    );
+    assert_eq!(transaction_history_with_balances(&transactions, "wallet_94").last().unwrap().1, 50);
+    assert!(transaction_history_with_balances(&transactions, "wallet_95").is_empty());
+
+    log_section_header("End Test: Transaction History With Balances");
+}
+
+#[test]
+fn test_canonicalize_ignores_order() {
+    // Test that equal histories in different orders render identically
+    initialize("test_canonicalize_ignores_order");
+    log_section_header("Start Test: Canonicalize Ignores Order");
+
+    let build = || {
+        vec![
+            timestamped(TransactionType::Deposit, "wallet_96", 100, base_time()),
+            Transaction {
+                memo: Some(String::from("split | \"rent\"\nline")),
+                ..timestamped(TransactionType::Withdrawal, "wallet_96", 40, base_time() + Duration::hours(1))
+            },
+            timestamped(
+                TransactionType::Transfer { to: String::from("wallet_97") },
+                "wallet_96",
+                10,
+                base_time() + Duration::hours(2),
+            ),
+        ]
+    };
+    let ordered = build();
+    let mut shuffled = build();
+    shuffled.rotate_left(1);
+    shuffled.swap(0, 1);
+
+    let canonical = canonicalize(&ordered);
+    assert_eq!(canonical, canonicalize(&shuffled));
+    assert_eq!(canonical.lines().count(), 3);
+    assert!(canonical.lines().next().unwrap().contains("Deposit \"wallet_96\" - 100 \"USD\""));
+    assert!(canonical.contains("\"wallet_97\""));
+
+    let mut different = build();
+    different[0].amount = Money::from_minor(101);
+    assert_ne!(canonical, canonicalize(&different));
+
+    // Histories differing only in idempotency keys are different histories
+    let mut keyed = build();
+    keyed[0].idempotency_key = Some(String::from("canonical-key"));
+    let keyed = canonicalize(&keyed);
+    assert_ne!(canonical, keyed);
+    assert!(keyed.contains("key=Some(\"canonical-key\")"));
+
+    log_section_header("End Test: Canonicalize Ignores Order");
+}
+
+#[test]
+fn test_balance_at() {
+    // Test querying the balance after a prefix of the history
+    initialize("test_balance_at");
+    log_section_header("Start Test: Balance At");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_98", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_98", 30),
+        Transaction::new(TransactionType::Deposit, "wallet_99", 500),
+        Transaction::new(TransactionType::Withdrawal, "wallet_98", 200),
+    ];
+
+    assert_eq!(balance_at(&transactions, "wallet_98", 0).unwrap(), 0);
+    assert_eq!(balance_at(&transactions, "wallet_98", 1).unwrap(), 100);
+    assert_eq!(balance_at(&transactions, "wallet_98", 3).unwrap(), 70);
+
+    // The overdraft only falls inside the window once it reaches the last transaction
+    assert!(matches!(
+        balance_at(&transactions, "wallet_98", 4),
+        Err(WalletError::InsufficientFunds { requested: 200, available: 70 })
+    ));
+    assert!(balance_at(&transactions, "wallet_98", 10).is_err());
+
+    let invalid = vec![Transaction::new(TransactionType::Deposit, "wallet_98", -5)];
+    assert!(matches!(
+        balance_at(&invalid, "wallet_98", 1),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Balance At");
+}
+
+#[test]
+fn test_turnover_ratio() {
+    // Test throughput divided by the average running balance
+    initialize("test_turnover_ratio");
+    log_section_header("Start Test: Turnover Ratio");
+
+    // Running balances 100, 50, 150 average to 100; throughput is 100 + 50 + 100 = 250
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_100", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_100", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_101", 900),
+        Transaction::new(TransactionType::Deposit, "wallet_100", 100),
+    ];
+    let ratio = turnover_ratio(&transactions, "wallet_100").unwrap().unwrap();
+    assert!((ratio - 2.5).abs() < 1e-9);
+
+    // Deposited then fully withdrawn still averages above zero
+    let drained = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_102", 80),
+        Transaction::new(TransactionType::Withdrawal, "wallet_102", 80),
+    ];
+    let ratio = turnover_ratio(&drained, "wallet_102").unwrap().unwrap();
+    assert!((ratio - 4.0).abs() < 1e-9);
+
+    // Moving i64::MAX in and out again is valid and must not overflow the throughput
+    let huge = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_102b", i64::MAX),
+        Transaction::new(TransactionType::Withdrawal, "wallet_102b", i64::MAX),
+    ];
+    let ratio = turnover_ratio(&huge, "wallet_102b").unwrap().unwrap();
+    assert!((ratio - 4.0).abs() < 1e-9);
+
+    let idle = vec![Transaction::new(TransactionType::Deposit, "wallet_103", 0)];
+    assert_eq!(turnover_ratio(&idle, "wallet_103").unwrap(), None);
+    assert_eq!(turnover_ratio(&transactions, "wallet_unknown").unwrap(), None);
+    assert!(turnover_ratio(&transactions[1..], "wallet_100").is_err());
+
+    log_section_header("End Test: Turnover Ratio");
+}
+
+#[test]
+fn test_verify_conservation() {
+    // Test that transfers net to zero and leaks out of the system are caught
+    initialize("test_verify_conservation");
+    log_section_header("Start Test: Verify Conservation");
+
+    let before: std::collections::HashMap<String, i64> = [
+        (String::from("wallet_104"), 500),
+        (String::from("wallet_105"), 200),
+        (String::from("wallet_106"), 0),
+    ]
+    .into_iter()
+    .collect();
+    let transfer = |from: &str, to: &str, amount: i64| {
+        Transaction::new(TransactionType::Transfer { to: to.to_string() }, from, amount)
+    };
+
+    let balanced = vec![
+        transfer("wallet_104", "wallet_105", 150),
+        transfer("wallet_105", "wallet_106", 300),
+        Transaction::new(TransactionType::Deposit, "wallet_106", 40),
+        Transaction::new(TransactionType::Withdrawal, "wallet_104", 100),
+        transfer("wallet_106", "wallet_104", 25),
+    ];
+    assert!(verify_conservation(&before, &balanced).is_ok());
+
+    // A transfer to a wallet outside the system makes money disappear
+    let mut leaking = balanced.clone();
+    leaking.push(transfer("wallet_105", "wallet_elsewhere", 30));
+    assert!(matches!(
+        verify_conservation(&before, &leaking),
+        Err(WalletError::ConservationViolation { expected: 640, actual: 610 })
+    ));
+
+    // Opening balances are honored when validating each wallet's replay
+    let overdraft = vec![transfer("wallet_105", "wallet_104", 250)];
+    assert!(matches!(
+        verify_conservation(&before, &overdraft),
+        Err(WalletError::WalletValidation { .. })
+    ));
+
+    log_section_header("End Test: Verify Conservation");
+}
+
+#[test]
+fn test_fees_deducted_from_balance() {
+    // Test that the paying wallet is charged fees on top of the transaction effect
+    initialize("test_fees_deducted_from_balance");
+    log_section_header("Start Test: Fees Deducted From Balance");
+
+    let with_fee = |transaction_type, amount, fee| Transaction {
+        fee,
+        ..Transaction::new(transaction_type, "wallet_107", amount)
+    };
+    let transfer = TransactionType::Transfer { to: String::from("wallet_108") };
+
+    let transactions = vec![
+        with_fee(TransactionType::Deposit, 200, 0),
+        with_fee(TransactionType::Withdrawal, 100, 5),
+    ];
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_107").unwrap(), 95);
+
+    // The sender pays the transfer fee; the receiver gets the full amount
+    let mut with_transfer = transactions.clone();
+    with_transfer.push(with_fee(transfer.clone(), 50, 2));
+    assert_eq!(calculate_wallet_balance(&with_transfer, "wallet_107").unwrap(), 43);
+    assert_eq!(calculate_wallet_balance(&with_transfer, "wallet_108").unwrap(), 50);
+
+    // The funds check covers both amount and fee
+    let overdraft = vec![
+        with_fee(TransactionType::Deposit, 100, 0),
+        with_fee(TransactionType::Withdrawal, 100, 1),
+    ];
+    assert!(matches!(
+        calculate_wallet_balance(&overdraft, "wallet_107"),
+        Err(WalletError::InsufficientFunds { requested: 101, available: 100 })
+    ));
+
+    let negative_fee = vec![with_fee(TransactionType::Deposit, 100, -3)];
+    assert!(matches!(
+        calculate_wallet_balance(&negative_fee, "wallet_107"),
+        Err(WalletError::InvalidAmount(-3))
+    ));
+
+    log_section_header("End Test: Fees Deducted From Balance");
+}
+
+#[test]
+fn test_shortfall() {
+    // Test the extra funds needed before a withdrawal can go through
+    initialize("test_shortfall");
+    log_section_header("Start Test: Shortfall");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_109", 80),
+        Transaction::new(TransactionType::Withdrawal, "wallet_109", 30),
+    ];
+
+    assert_eq!(shortfall(&transactions, "wallet_109", 120).unwrap(), 70);
+    assert_eq!(shortfall(&transactions, "wallet_109", 50).unwrap(), 0);
+    assert_eq!(shortfall(&transactions, "wallet_109", 10).unwrap(), 0);
+    assert_eq!(shortfall(&transactions, "wallet_unknown", 25).unwrap(), 25);
+    assert!(matches!(
+        shortfall(&transactions, "wallet_109", -1),
+        Err(WalletError::InvalidAmount(-1))
+    ));
+
+    log_section_header("End Test: Shortfall");
+}
+
+#[test]
+fn test_apply_interest() {
+    // Test accruing simple interest as a deposit on the current balance
+    initialize("test_apply_interest");
+    log_section_header("Start Test: Apply Interest");
+
+    let mut transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_110", 12_000),
+        Transaction::new(TransactionType::Withdrawal, "wallet_110", 2_000),
+    ];
+
+    // 2.5% of 10,000 is 250
+    assert_eq!(apply_interest(&mut transactions, "wallet_110", 250).unwrap(), 250);
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[2].memo.as_deref(), Some("interest"));
+    assert_eq!(calculate_wallet_balance(&transactions, "wallet_110").unwrap(), 10_250);
+
+    // Fractional interest rounds down, and nothing is added when it rounds to zero
+    assert_eq!(apply_interest(&mut transactions, "wallet_110", 0).unwrap(), 0);
+    let mut tiny = vec![Transaction::new(TransactionType::Deposit, "wallet_111", 99)];
+    assert_eq!(apply_interest(&mut tiny, "wallet_111", 100).unwrap(), 0);
+    assert_eq!(tiny.len(), 1);
+
+    let mut empty = Vec::new();
+    assert_eq!(apply_interest(&mut empty, "wallet_112", 500).unwrap(), 0);
+    assert!(empty.is_empty());
+
+    let mut huge = vec![Transaction::new(TransactionType::Deposit, "wallet_113", i64::MAX / 2)];
+    assert!(matches!(
+        apply_interest(&mut huge, "wallet_113", u32::MAX),
+        Err(WalletError::BalanceOverflow { .. })
+    ));
+    assert_eq!(huge.len(), 1);
+
+    log_section_header("End Test: Apply Interest");
+}
+
+#[test]
+fn test_remaining_lots() {
+    // Test FIFO consumption of deposit lots by withdrawals
+    initialize("test_remaining_lots");
+    log_section_header("Start Test: Remaining Lots");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_114", 40),
+        Transaction::new(TransactionType::Deposit, "wallet_114", 70),
+        Transaction::new(TransactionType::Withdrawal, "wallet_114", 50),
+    ];
+
+    // The first lot is fully consumed and drops out; the second keeps 60
+    assert_eq!(
+        remaining_lots(&transactions, "wallet_114").unwrap(),
+        vec![(transactions[1].id, 60)]
+    );
+    assert_eq!(remaining_lots(&transactions[..2], "wallet_114").unwrap().len(), 2);
+    assert!(remaining_lots(&transactions, "wallet_unknown").unwrap().is_empty());
+    assert_eq!(remaining_lots(&transactions[1..], "wallet_114").unwrap(), vec![(transactions[1].id, 20)]);
+    assert!(remaining_lots(&transactions[2..], "wallet_114").is_err());
+
+    log_section_header("End Test: Remaining Lots");
+}
+
+#[test]
+fn test_balance_without_last_n() {
+    // Test previewing a rollback of a wallet's most recent transactions
+    initialize("test_balance_without_last_n");
+    log_section_header("Start Test: Balance Without Last N");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_115", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_115", 20),
+        Transaction::new(TransactionType::Deposit, "wallet_115", 50),
+        Transaction::new(TransactionType::Deposit, "wallet_116", 999),
+        Transaction::new(TransactionType::Withdrawal, "wallet_115", 70),
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_116") }, "wallet_115", 10),
+    ];
+
+    // Dropping the transfer and the last withdrawal leaves 100 - 20 + 50
+    assert_eq!(balance_without_last_n(&transactions, "wallet_115", 2).unwrap(), 130);
+    assert_eq!(balance_without_last_n(&transactions, "wallet_115", 0).unwrap(), 50);
+    assert_eq!(balance_without_last_n(&transactions, "wallet_115", 5).unwrap(), 0);
+    assert_eq!(balance_without_last_n(&transactions, "wallet_115", 50).unwrap(), 0);
+    assert_eq!(balance_without_last_n(&transactions, "wallet_116", 1).unwrap(), 999);
+
+    log_section_header("End Test: Balance Without Last N");
+}
+
+#[test]
+fn test_list_wallets() {
+    // Test that wallet listings are unique, sorted, and include transfer destinations
+    initialize("test_list_wallets");
+    log_section_header("Start Test: List Wallets");
+
+    assert!(list_wallets(&[]).is_empty());
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_118", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_117", 50),
+        Transaction::new(TransactionType::Withdrawal, "wallet_118", 20),
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_119") }, "wallet_117", 10),
+    ];
+    assert_eq!(list_wallets(&transactions), vec!["wallet_117", "wallet_118", "wallet_119"]);
+
+    log_section_header("End Test: List Wallets");
+}
+
+#[test]
+fn test_totals_by_memo_prefix() {
+    // Test grouping a wallet's balance changes by the category before the separator
+    initialize("test_totals_by_memo_prefix");
+    log_section_header("Start Test: Totals By Memo Prefix");
+
+    let tagged = |transaction_type, wallet, amount, memo: &str| Transaction {
+        memo: Some(String::from(memo)),
+        ..Transaction::new(transaction_type, wallet, amount)
+    };
+    let transactions = vec![
+        tagged(TransactionType::Deposit, "wallet_120", 5000, "salary"),
+        tagged(TransactionType::Withdrawal, "wallet_120", 1200, "food:lunch"),
+        tagged(TransactionType::Withdrawal, "wallet_120", 2500, "food:dinner"),
+        Transaction::new(TransactionType::Withdrawal, "wallet_120", 300),
+        tagged(TransactionType::Withdrawal, "wallet_121", 700, "food:lunch"),
+    ];
+
+    let totals = totals_by_memo_prefix(&transactions, "wallet_120", ':').unwrap();
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals["food"], -3700);
+    assert_eq!(totals["salary"], 5000);
+
+    // A category's total that leaves the i64 range is an overflow; an unvalidated
+    // amount saturates inside a single transaction's delta instead of panicking
+    let huge = vec![
+        tagged(TransactionType::Withdrawal, "wallet_120", i64::MAX, "tax:annual"),
+        Transaction { fee: 2, ..tagged(TransactionType::Withdrawal, "wallet_120", i64::MAX, "tax:late") },
+    ];
+    assert!(matches!(
+        totals_by_memo_prefix(&huge, "wallet_120", ':'),
+        Err(WalletError::BalanceOverflow { .. })
+    ));
+
+    log_section_header("End Test: Totals By Memo Prefix");
+}
+
+#[test]
+fn test_wallet_summary() {
+    // Test the activity overview, including the largest transaction and invalid histories
+    initialize("test_wallet_summary");
+    log_section_header("Start Test: Wallet Summary");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_122", 800),
+        Transaction {
+            fee: 5,
+            ..Transaction::new(TransactionType::Withdrawal, "wallet_122", 200)
+        },
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_122") }, "wallet_123", 800),
+        Transaction::new(TransactionType::Deposit, "wallet_123", 5000),
+    ];
+
+    let summary = wallet_summary(&transactions, "wallet_122").unwrap();
+    assert_eq!(summary.wallet_address, "wallet_122");
+    assert_eq!(summary.total_deposits, 1600);
+    assert_eq!(summary.total_withdrawals, 205);
+    assert_eq!(summary.transaction_count, 3);
+    assert_eq!(summary.balance, 1395);
+    assert_eq!(summary.largest_transaction, Some(transactions[0].clone()));
+
+    let empty = wallet_summary(&transactions, "wallet_124").unwrap();
+    assert_eq!(empty.transaction_count, 0);
+    assert!(empty.largest_transaction.is_none());
+
+    let invalid = vec![Transaction::new(TransactionType::Deposit, "wallet_122", -10)];
+    assert!(matches!(wallet_summary(&invalid, "wallet_122"), Err(WalletError::InvalidAmount(-10))));
+
+    log_section_header("End Test: Wallet Summary");
+}
+
+#[test]
+fn test_balance_sparkline() {
+    // Test that the sparkline has one block per transaction scaled to the balance range
+    initialize("test_balance_sparkline");
+    log_section_header("Start Test: Balance Sparkline");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_125", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_125", 700),
+        Transaction::new(TransactionType::Deposit, "wallet_126", 5000),
+        Transaction::new(TransactionType::Withdrawal, "wallet_125", 400),
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_126") }, "wallet_125", 300),
+    ];
+
+    let sparkline = balance_sparkline(&transactions, "wallet_125").unwrap();
+    let blocks: Vec<char> = sparkline.chars().collect();
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(sparkline, "▁█▄▁");
+    assert!(blocks[1] > blocks[2] && blocks[2] > blocks[3]);
+
+    let flat = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_127", 100),
+        Transaction::new(TransactionType::Deposit, "wallet_127", 0),
+    ];
+    assert_eq!(balance_sparkline(&flat, "wallet_127").unwrap(), "▁▁");
+    assert_eq!(balance_sparkline(&transactions, "wallet_128").unwrap(), "");
+
+    log_section_header("End Test: Balance Sparkline");
+}
+
+#[test]
+fn test_calculate_wallet_balance_with_overdraft() {
+    // Test that withdrawals may overdraw the wallet only down to the limit
+    initialize("test_calculate_wallet_balance_with_overdraft");
+    log_section_header("Start Test: Calculate Wallet Balance With Overdraft");
+
+    let mut transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_129", 100),
+        Transaction::new(TransactionType::Withdrawal, "wallet_129", 130),
+    ];
+    assert_eq!(calculate_wallet_balance_with_overdraft(&transactions, "wallet_129", 50).unwrap(), -30);
+    assert!(matches!(
+        calculate_wallet_balance(&transactions, "wallet_129"),
+        Err(WalletError::InsufficientFunds { requested: 130, available: 100 })
+    ));
+
+    // Landing exactly on the limit is allowed; going one unit past it is not
+    transactions.push(Transaction {
+        fee: 5,
+        ..Transaction::new(TransactionType::Withdrawal, "wallet_129", 15)
+    });
+    assert_eq!(calculate_wallet_balance_with_overdraft(&transactions, "wallet_129", 50).unwrap(), -50);
+    transactions.push(Transaction::new(TransactionType::Transfer { to: String::from("wallet_130") }, "wallet_129", 1));
+    assert!(matches!(
+        calculate_wallet_balance_with_overdraft(&transactions, "wallet_129", 50),
+        Err(WalletError::InsufficientFunds { requested: 1, available: 0 })
+    ));
+
+    assert!(matches!(
+        calculate_wallet_balance_with_overdraft(&transactions, "wallet_129", -1),
+        Err(WalletError::InvalidAmount(-1))
+    ));
+
+    log_section_header("End Test: Calculate Wallet Balance With Overdraft");
+}
+
+#[test]
+fn test_sum_by_type() {
+    // Test totaling deposits and withdrawals independently of each other
+    initialize("test_sum_by_type");
+    log_section_header("Start Test: Sum By Type");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "wallet_131", 500),
+        Transaction {
+            fee: 10,
+            ..Transaction::new(TransactionType::Withdrawal, "wallet_131", 120)
+        },
+        Transaction::new(TransactionType::Deposit, "wallet_131", 250),
+        Transaction::new(TransactionType::Deposit, "wallet_132", 900),
+        Transaction::new(TransactionType::Withdrawal, "wallet_131", 30),
+        Transaction::new(TransactionType::Transfer { to: String::from("wallet_132") }, "wallet_131", 40),
+    ];
+
+    assert_eq!(sum_by_type(&transactions, "wallet_131", &TransactionType::Deposit).unwrap(), 750);
+    assert_eq!(sum_by_type(&transactions, "wallet_131", &TransactionType::Withdrawal).unwrap(), 150);
+    let transfer = TransactionType::Transfer { to: String::from("wallet_132") };
+    assert_eq!(sum_by_type(&transactions, "wallet_132", &transfer).unwrap(), 40);
+    assert_eq!(sum_by_type(&transactions, "wallet_132", &TransactionType::Withdrawal).unwrap(), 0);
+
+    let invalid = vec![Transaction::new(TransactionType::Withdrawal, "wallet_131", -5)];
+    assert!(matches!(
+        sum_by_type(&invalid, "wallet_131", &TransactionType::Withdrawal),
+        Err(WalletError::InvalidAmount(-5))
+    ));
+
+    log_section_header("End Test: Sum By Type");
+}
+
+#[test]
+fn test_transaction_equality() {
+    // Test that transactions compare equal field by field
+    initialize("test_transaction_equality");
+    log_section_header("Start Test: Transaction Equality");
+
+    let tx = Transaction::new(TransactionType::Transfer { to: String::from("wallet_134") }, "wallet_133", 75);
+    assert_eq!(tx.clone(), tx);
+    assert_ne!(Transaction { fee: 1, ..tx.clone() }, tx);
+    assert_ne!(
+        Transaction {
+            transaction_type: TransactionType::Transfer { to: String::from("wallet_135") },
+            ..tx.clone()
+        },
+        tx
+    );
+    assert_eq!(tx.transaction_type, TransactionType::Transfer { to: String::from("wallet_134") });
+    assert_ne!(TransactionType::Deposit, TransactionType::Withdrawal);
+
+    log_section_header("End Test: Transaction Equality");
+}
+
+#[test]
+fn test_wallet_index_matches_naive_balances() {
+    // Test indexed balance lookups against full scans on a large generated log
+    initialize("test_wallet_index_matches_naive_balances");
+    log_section_header("Start Test: Wallet Index Matches Naive Balances");
+
+    const WALLETS: usize = 40;
+    let wallet = |n: usize| format!("indexed_{}", n);
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    // Withdrawals and transfers are only generated when the source can afford them
+    let mut balances = [0i64; WALLETS];
+    let mut transactions = Vec::new();
+    let mut index = WalletIndex::new();
+    for _ in 0..20_000 {
+        let source = (next() % WALLETS as u64) as usize;
+        let amount = 1 + (next() % 500) as i64;
+        let tx = match next() % 3 {
+            1 if balances[source] >= amount => {
+                balances[source] -= amount;
+                Transaction::new(TransactionType::Withdrawal, wallet(source), amount)
+            }
+            2 if balances[source] >= amount => {
+                let destination = (next() % WALLETS as u64) as usize;
+                balances[source] -= amount;
+                balances[destination] += amount;
+                Transaction::new(TransactionType::Transfer { to: wallet(destination) }, wallet(source), amount)
+            }
+            _ => {
+                balances[source] += amount;
+                Transaction::new(TransactionType::Deposit, wallet(source), amount)
+            }
+        };
+        index.record(transactions.len(), &tx);
+        transactions.push(tx);
+    }
+    assert_eq!(index, WalletIndex::build(&transactions));
+
+    let started = std::time::Instant::now();
+    let naive: Vec<i64> = (0..WALLETS)
+        .map(|n| calculate_wallet_balance(&transactions, &wallet(n)).unwrap())
+        .collect();
+    let naive_time = started.elapsed();
+    let started = std::time::Instant::now();
+    let indexed: Vec<i64> = (0..WALLETS)
+        .map(|n| index.balance(&transactions, &wallet(n)).unwrap())
+        .collect();
+    let indexed_time = started.elapsed();
+    log::info!("Balances for {} wallets: naive {:?}, indexed {:?}", WALLETS, naive_time, indexed_time);
+
+    assert_eq!(indexed, naive);
+    assert_eq!(indexed, balances.to_vec());
+    assert_eq!(index.balance(&transactions, "indexed_unknown").unwrap(), 0);
+
+    // An invalid indexed transaction fails the same way as a full scan
+    let mut invalid = transactions[..10].to_vec();
+    invalid.push(Transaction::new(TransactionType::Withdrawal, wallet(0), i64::MAX));
+    let mut invalid_index = WalletIndex::build(&invalid);
+    assert!(matches!(
+        invalid_index.balance(&invalid, &wallet(0)),
+        Err(WalletError::InsufficientFunds { requested: i64::MAX, .. })
+    ));
+    let popped = invalid.pop().unwrap();
+    invalid_index.forget(invalid.len(), &popped);
+    assert_eq!(invalid_index, WalletIndex::build(&invalid));
+
+    log_section_header("End Test: Wallet Index Matches Naive Balances");
+}
+
+#[test]
+fn test_wallet_index_reports_stale_positions() {
+    // Test that an index used against a log it no longer describes fails instead of skipping entries
+    initialize("test_wallet_index_reports_stale_positions");
+    log_section_header("Start Test: Wallet Index Reports Stale Positions");
+
+    let transactions = vec![
+        Transaction::new(TransactionType::Deposit, "stale_a".to_string(), 100),
+        Transaction::new(TransactionType::Deposit, "stale_b".to_string(), 50),
+        Transaction::new(TransactionType::Withdrawal, "stale_a".to_string(), 40),
+    ];
+    let index = WalletIndex::build(&transactions);
+    assert_eq!(index.balance(&transactions, "stale_a").unwrap(), 60);
+
+    // A truncated log leaves position 2 out of range
+    assert!(matches!(
+        index.balance(&transactions[..2], "stale_a"),
+        Err(WalletError::StaleIndex { ref wallet, position: 2 }) if wallet == "stale_a"
+    ));
+
+    // A reordered log puts another wallet's transaction at an indexed position
+    let reordered = vec![transactions[1].clone(), transactions[0].clone(), transactions[2].clone()];
+    let error = index.balance(&reordered, "stale_a").unwrap_err();
+    assert!(matches!(error, WalletError::StaleIndex { position: 0, .. }));
+    assert_eq!(error.kind(), "StaleIndex");
+
+    log_section_header("End Test: Wallet Index Reports Stale Positions");
+}
+
+// Terminal-specific test module
+mod terminal_tests {
+    use super::*;
+    use ryz_labs::terminal::WalletTerminal;
+
+    // Initialize terminal instance for testing
+    fn setup_terminal() -> WalletTerminal {
+        initialize("test_terminal");
+        WalletTerminal::new()
+    }
+
+    #[test]
+    fn test_terminal_creation() {
+        // Test terminal initialization
+        let _terminal = setup_terminal();
+        let result = calculate_wallet_balance(&[], "test_wallet").unwrap();
+        assert_eq!(result, 0);
+    }
+
+    // Helper function to process test transactions
+    fn execute_transactions(transactions: Vec<Transaction>) -> Result<i64, WalletError> {
+        let wallet_address = &transactions[0].wallet_address;
+        calculate_wallet_balance(&transactions, wallet_address)
+    }
+
+    #[test]
+    fn test_terminal_operations() {
+        // Test basic terminal transaction operations
+        let wallet = "test_wallet";
+        let deposit_amount = 100;
+        let withdrawal_amount = 30;
+
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, wallet, deposit_amount),
+            Transaction::new(TransactionType::Withdrawal, wallet, withdrawal_amount),
+        ];
+
+        let final_balance = execute_transactions(transactions).unwrap();
+        assert_eq!(final_balance, deposit_amount - withdrawal_amount);
+    }
+
+    #[test]
+    fn test_terminal_insufficient_funds() {
+        // Test terminal handling of insufficient funds
+        let wallet = "test_wallet";
+        
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, wallet, 50),
+            Transaction::new(TransactionType::Withdrawal, wallet, 100),
+        ];
+
+        let result = execute_transactions(transactions);
+        assert!(matches!(
+            result,
+            Err(WalletError::InsufficientFunds {
+                requested: 100,
+                available: 50
+            })
+        ));
+    }
+
+    #[test]
+    fn test_terminal_multiple_wallets() {
+        // Test terminal handling of multiple wallet operations
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, "wallet1", 100),
+            Transaction::new(TransactionType::Deposit, "wallet2", 200),
+        ];
+
+        let balance1 = calculate_wallet_balance(&transactions, "wallet1").unwrap();
+        let balance2 = calculate_wallet_balance(&transactions, "wallet2").unwrap();
+
+        assert_eq!(balance1, 100);
+        assert_eq!(balance2, 200);
+    }
+
+    #[test]
+    fn test_terminal_transaction_history() {
+        // Test terminal transaction history functionality
+        let wallet = "history_wallet";
+        
+        let transactions = vec![
+            timestamped(TransactionType::Deposit, wallet, 100, base_time()),
+            timestamped(TransactionType::Withdrawal, wallet, 30, base_time()),
+            timestamped(TransactionType::Deposit, wallet, 50, base_time()),
+        ];
+
+        let balance = calculate_wallet_balance(&transactions, wallet).unwrap();
+        assert_eq!(balance, 120);
+
+        // Verify transaction display format
+        let tx = &transactions[0];
+        assert_eq!(
+            format!("{}", tx),
+            "2024-01-01 12:00:00 | Deposit of 1.00 to history_wallet"
+        );
+    }
+
+    #[test]
+    fn test_terminal_checkpoint_rewind() {
+        // Test rewinding the session log to a named checkpoint
+        let mut terminal = setup_terminal();
+        terminal.add_deposit("checkpoint_wallet", 100).unwrap();
+        terminal.checkpoint("funded").unwrap();
+
+        terminal.add_deposit("checkpoint_wallet", 50).unwrap();
+        terminal.add_withdrawal("checkpoint_wallet", 30).unwrap();
+        assert_eq!(
+            calculate_wallet_balance(&terminal.transactions().unwrap(), "checkpoint_wallet").unwrap(),
+            120
+        );
+
+        assert!(terminal.rewind_to("funded").unwrap());
+        assert_eq!(terminal.transactions().unwrap().len(), 1);
+        assert_eq!(
+            calculate_wallet_balance(&terminal.transactions().unwrap(), "checkpoint_wallet").unwrap(),
+            100
+        );
+
+        // Unknown checkpoints leave the log untouched
+        assert!(!terminal.rewind_to("missing").unwrap());
+        assert_eq!(terminal.transactions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_terminal_rejects_invalid_transaction() {
+        // Test that recording an overdraft leaves the session log unchanged
+        let mut terminal = setup_terminal();
+        let result = terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "empty_wallet", 10));
+        assert!(matches!(
+            result,
+            Err(WalletError::InsufficientFunds {
+                requested: 10,
+                available: 0
+            })
+        ));
+        assert!(terminal.transactions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_terminal_daily_withdrawal_limit() {
+        // Test that the terminal rejects a withdrawal breaching today's limit
+        initialize("test_terminal");
+        let mut terminal = WalletTerminal::with_daily_withdrawal_limit(100);
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "limited_wallet", 500))
+            .unwrap();
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "limited_wallet", 60))
+            .unwrap();
+
+        let result = terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "limited_wallet", 50));
+        assert!(matches!(
+            result,
+            Err(WalletError::DailyLimitExceeded { limit: 100, .. })
+        ));
+        assert_eq!(
+            calculate_wallet_balance(&terminal.transactions().unwrap(), "limited_wallet").unwrap(),
+            440
+        );
+
+        // Transfers out of the wallet and their fees use up the same limit
+        let result = terminal.record_transaction(Transaction::new(
+            TransactionType::Transfer { to: "limited_other".to_string() },
+            "limited_wallet",
+            50,
+        ));
+        assert!(matches!(result, Err(WalletError::DailyLimitExceeded { limit: 100, .. })));
+        let mut with_fee = Transaction::new(
+            TransactionType::Transfer { to: "limited_other".to_string() },
+            "limited_wallet",
+            35,
+        );
+        with_fee.fee = 6;
+        assert!(matches!(
+            terminal.record_transaction(with_fee),
+            Err(WalletError::DailyLimitExceeded { limit: 100, .. })
+        ));
+        terminal
+            .record_transaction(Transaction::new(
+                TransactionType::Transfer { to: "limited_other".to_string() },
+                "limited_wallet",
+                40,
+            ))
+            .unwrap();
+        assert_eq!(terminal.wallet_balance("limited_wallet").unwrap(), 400);
+    }
+
+    #[test]
+    fn test_terminal_rankings() {
+        // Test that rankings list wallets in descending balance order
+        let mut terminal = setup_terminal();
+
+        let mut output = Vec::new();
+        terminal.write_rankings(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "No wallets recorded yet\n");
+
+        for (wallet, amount) in [("rank_low", 10), ("rank_high", 300), ("rank_mid", 150)] {
+            terminal
+                .record_transaction(Transaction::new(TransactionType::Deposit, wallet, amount))
+                .unwrap();
+        }
+
+        let mut output = Vec::new();
+        terminal.write_rankings(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Wallet rankings by balance:",
+                "1. rank_high | Balance: 3.00",
+                "2. rank_mid | Balance: 1.50",
+                "3. rank_low | Balance: 0.10",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_terminal_export_report() {
+        // Test that the session report matches the individual computations
+        let mut terminal = setup_terminal();
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "report_a", 200))
+            .unwrap();
+        terminal
+            .record_transaction(Transaction {
+                fee: 3,
+                ..Transaction::new(TransactionType::Withdrawal, "report_a", 50)
+            })
+            .unwrap();
+        terminal
+            .record_transaction(Transaction::new(
+                TransactionType::Transfer {
+                    to: String::from("report_b"),
+                },
+                "report_a",
+                40,
+            ))
+            .unwrap();
+
+        let report = terminal.export_report().unwrap();
+        let transactions = &terminal.transactions().unwrap();
+        assert_eq!(report.balances["report_a"], calculate_wallet_balance(transactions, "report_a").unwrap());
+        assert_eq!(report.balances["report_b"], calculate_wallet_balance(transactions, "report_b").unwrap());
+        assert_eq!(report.transaction_count, 3);
+        assert_eq!(report.wallet_count, 2);
+        assert_eq!(report.total_deposited, 200);
+        assert_eq!(report.total_withdrawn, 50);
+        assert_eq!(report.total_transferred, 40);
+        assert_eq!(report.total_fees, total_fee_revenue(transactions));
+        assert_eq!(report.history_hash, chain_hashes(transactions).last().cloned());
+
+        // Totals across wallets can overflow even when every wallet's balance fits
+        terminal.add_deposit("report_c", i64::MAX).unwrap();
+        assert!(matches!(
+            terminal.export_report(),
+            Err(WalletError::BalanceOverflow { ref wallet }) if wallet == "report_c"
+        ));
+    }
+
+    #[test]
+    fn test_replay_with_mock_sleeper() {
+        // Test that a replay applies every transaction and pauses once per step
+        struct RecordingSleeper(Vec<std::time::Duration>);
+
+        impl ryz_labs::terminal::Sleeper for RecordingSleeper {
+            fn sleep(&mut self, duration: std::time::Duration) {
+                self.0.push(duration);
+            }
+        }
+
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Replay With Mock Sleeper");
+
+        let history = vec![
+            Transaction::new(TransactionType::Deposit, "wallet_52", 200),
+            Transaction::new(TransactionType::Withdrawal, "wallet_52", 50),
+            Transaction::new(TransactionType::Deposit, "wallet_53", 75),
+        ];
+        let delay = std::time::Duration::from_millis(500);
+        let mut sleeper = RecordingSleeper(Vec::new());
+        terminal.replay_with_sleeper(&history, delay, &mut sleeper);
+
+        assert_eq!(terminal.transactions().unwrap().len(), 3);
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "wallet_52").unwrap(), 150);
+        assert_eq!(sleeper.0, vec![delay; 3]);
+
+        log_section_header("End Test: Replay With Mock Sleeper");
+    }
+
+    #[test]
+    fn test_tail_log_lines() {
+        // Test reading the last lines of a log, including short and missing files
+        let terminal = setup_terminal();
+        log_section_header("Start Test: Tail Log Lines");
+
+        let path = std::env::temp_dir().join(format!("ryz_labs_tail_{}.log", std::process::id()));
+        std::fs::write(&path, "first entry\nsecond entry\nthird entry\nfourth entry\n").unwrap();
+
+        assert_eq!(
+            ryz_labs::terminal::tail_lines(&path, 2).unwrap(),
+            vec!["third entry", "fourth entry"]
+        );
+        assert_eq!(ryz_labs::terminal::tail_lines(&path, 10).unwrap().len(), 4);
+        assert!(ryz_labs::terminal::tail_lines(&path, 0).unwrap().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(ryz_labs::terminal::tail_lines(&path, 3).unwrap().is_empty());
+        assert!(terminal.tail_log(5).unwrap().len() <= 5);
+
+        log_section_header("End Test: Tail Log Lines");
+    }
+
+    #[test]
+    fn test_terminal_transfer_moves_funds() {
+        // Test that a recorded transfer debits the source and credits the destination
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Terminal Transfer Moves Funds");
+
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "transfer_source", 100))
+            .unwrap();
+        let transfer = |amount| {
+            Transaction::new(
+                TransactionType::Transfer { to: String::from("transfer_destination") },
+                "transfer_source",
+                amount,
+            )
+        };
+        terminal.record_transaction(transfer(70)).unwrap();
+        assert!(matches!(
+            terminal.record_transaction(transfer(50)),
+            Err(WalletError::InsufficientFunds { requested: 50, available: 30 })
+        ));
+
+        assert_eq!(terminal.transactions().unwrap().len(), 2);
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "transfer_source").unwrap(), 30);
+        assert_eq!(
+            calculate_wallet_balance(&terminal.transactions().unwrap(), "transfer_destination").unwrap(),
+            70
+        );
+
+        log_section_header("End Test: Terminal Transfer Moves Funds");
+    }
+
+    #[test]
+    fn test_terminal_save_and_load_round_trip() {
+        // Test that a saved session reloads with the same transactions and balances
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Terminal Save And Load Round Trip");
+
+        terminal
+            .record_transaction(Transaction {
+                memo: Some(String::from("paycheck")),
+                ..Transaction::new(TransactionType::Deposit, "persist_wallet", 500)
+            })
+            .unwrap();
+        terminal
+            .record_transaction(Transaction::new(
+                TransactionType::Transfer { to: String::from("persist_other") },
+                "persist_wallet",
+                120,
+            ))
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("ryz_labs_state_{}.json", std::process::id()));
+        terminal.save_to_file(&path).unwrap();
+        let loaded = WalletTerminal::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded.transactions().unwrap(), terminal.transactions().unwrap());
+        assert_eq!(calculate_wallet_balance(&loaded.transactions().unwrap(), "persist_wallet").unwrap(), 380);
+        assert_eq!(calculate_wallet_balance(&loaded.transactions().unwrap(), "persist_other").unwrap(), 120);
+
+        // New transactions must not reuse identifiers from the loaded history
+        let fresh = Transaction::new(TransactionType::Deposit, "persist_wallet", 1);
+        assert!(loaded.transactions().unwrap().iter().all(|tx| tx.id < fresh.id));
+
+        std::fs::remove_file(&path).unwrap();
+        log_section_header("End Test: Terminal Save And Load Round Trip");
+    }
+
+    #[test]
+    fn test_terminal_load_rejects_corrupt_file() {
+        // Test that malformed or overdrawn state files are reported as invalid data
+        initialize("test_terminal");
+        log_section_header("Start Test: Terminal Load Rejects Corrupt File");
+
+        let path = std::env::temp_dir().join(format!("ryz_labs_corrupt_{}.json", std::process::id()));
+        std::fs::write(&path, "{ not json").unwrap();
+        let err = WalletTerminal::load_from_file(&path).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let overdrawn = vec![Transaction::new(TransactionType::Withdrawal, "corrupt_wallet", 10)];
+        std::fs::write(&path, serde_json::to_string(&overdrawn).unwrap()).unwrap();
+        let err = WalletTerminal::load_from_file(&path).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        std::fs::remove_file(&path).unwrap();
+        log_section_header("End Test: Terminal Load Rejects Corrupt File");
+    }
+
+    #[test]
+    fn test_terminal_run_script() {
+        // Test executing scripted commands, continuing past invalid lines
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Terminal Run Script");
+
+        let script = "\
+# opening balances
+deposit script_1 100
+withdraw script_1 30
+withdraw script_1 500
+transfer script_1 script_2 50
+deposit script_2 abc
+fly script_1 10
+balance script_1
+deposit script_2 5.25
+";
+        terminal.run_script(std::io::Cursor::new(script)).unwrap();
+
+        // Script amounts are in major units; balances are in minor units
+        assert_eq!(terminal.transactions().unwrap().len(), 4);
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "script_1").unwrap(), 2_000);
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "script_2").unwrap(), 5_525);
+
+        log_section_header("End Test: Terminal Run Script");
+    }
+
+    #[test]
+    fn test_terminal_get_amount_reprompts() {
+        // Test that garbage input is re-prompted and distinguished from a real zero
+        initialize("test_terminal");
+        log_section_header("Start Test: Terminal Get Amount Reprompts");
+
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("abc\n12x\n100\n"));
+        assert_eq!(terminal.get_amount().unwrap(), Money::from_major(100));
+
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("0\n"));
+        assert_eq!(terminal.get_amount().unwrap(), Some(Money::ZERO));
+
+        // Amounts are read in major units with up to two decimal places
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("1.005\n10.50\n"));
+        assert_eq!(terminal.get_amount().unwrap(), Some(Money::from_minor(1050)));
+
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("one\ntwo\nthree\n100\n"));
+        assert_eq!(terminal.get_amount().unwrap(), None);
+
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("oops\n"));
+        assert_eq!(terminal.get_amount().unwrap(), None);
+
+        log_section_header("End Test: Terminal Get Amount Reprompts");
+    }
+
+    #[test]
+    fn test_terminal_export_history_csv() {
+        // Test exporting a session wallet's history to a CSV file
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Terminal Export History CSV");
+
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "csv,wallet", 100))
+            .unwrap();
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "csv,wallet", 25))
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("ryz_labs_history_{}.csv", std::process::id()));
+        terminal.export_history_csv("csv,wallet", &path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "type,wallet,amount,running_balance");
+        assert_eq!(lines[1], "\"Deposit\",\"csv,wallet\",1.00,1.00");
+        assert_eq!(lines[2], "\"Withdrawal\",\"csv,wallet\",0.25,0.75");
+
+        std::fs::remove_file(&path).unwrap();
+        log_section_header("End Test: Terminal Export History CSV");
+    }
+
+    #[test]
+    fn test_terminal_max_amount() {
+        // Test that transactions above the configured ceiling are rejected
+        initialize("test_terminal");
+        log_section_header("Start Test: Terminal Max Amount");
+
+        let mut terminal = WalletTerminal::with_max_amount(1000);
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "max_wallet", 1000))
+            .unwrap();
+
+        let result = terminal.record_transaction(Transaction::new(TransactionType::Deposit, "max_wallet", 10_000));
+        assert!(matches!(
+            result,
+            Err(WalletError::AmountExceedsLimit { amount: 10_000, limit: 1000 })
+        ));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Transaction amount 10000 exceeds the limit of 1000"
+        );
+        assert!(matches!(
+            terminal.record_transaction(Transaction::new(TransactionType::Withdrawal, "max_wallet", 1001)),
+            Err(WalletError::AmountExceedsLimit { .. })
+        ));
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "max_wallet", 400))
+            .unwrap();
+
+        assert_eq!(terminal.transactions().unwrap().len(), 2);
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "max_wallet").unwrap(), 600);
+
+        log_section_header("End Test: Terminal Max Amount");
+    }
+
+    #[test]
+    fn test_terminal_rejects_invalid_addresses() {
+        // Test that commands naming invalid addresses are rejected at entry
+        let mut terminal = setup_terminal();
+        log_section_header("Start Test: Terminal Rejects Invalid Addresses");
+
+        let script = "\
+deposit valid_wallet 100
+deposit bad-wallet 100
+transfer valid_wallet bad!wallet 10
+withdraw valid_wallet 40
+";
+        terminal.run_script(std::io::Cursor::new(script)).unwrap();
+
+        assert_eq!(terminal.transactions().unwrap().len(), 2);
+        assert!(terminal.transactions().unwrap().iter().all(|tx| tx.wallet_address == "valid_wallet"));
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "valid_wallet").unwrap(), 6_000);
+
+        log_section_header("End Test: Terminal Rejects Invalid Addresses");
+    }
+
+    #[test]
+    fn test_terminal_get_fee() {
+        // Test reading optional fees, where blank input means no fee
+        initialize("test_terminal");
+        log_section_header("Start Test: Terminal Get Fee");
+
+        let mut terminal = WalletTerminal::with_input(std::io::Cursor::new("\n2.50\n-1\n"));
+        assert_eq!(terminal.get_fee().unwrap(), Some(Money::ZERO));
+        assert_eq!(terminal.get_fee().unwrap(), Some(Money::from_minor(250)));
+        assert_eq!(terminal.get_fee().unwrap(), None);
+        assert_eq!(terminal.get_fee().unwrap(), None);
+
+        log_section_header("End Test: Terminal Get Fee");
+    }
+
+    #[test]
+    fn test_terminal_wallet_list() {
+        // Test that the wallet listing shows every address with its balance
+        let mut terminal = setup_terminal();
+
+        let mut output = Vec::new();
+        terminal.write_wallet_list(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "No wallets recorded yet\n");
+
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "list_b", 500))
+            .unwrap();
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Transfer { to: String::from("list_a") }, "list_b", 125))
+            .unwrap();
+
+        let mut output = Vec::new();
+        terminal.write_wallet_list(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["Known wallets:", "list_a | Balance: 1.25", "list_b | Balance: 3.75"]
+        );
+    }
+
+    #[test]
+    fn test_terminal_rejects_duplicate_key() {
+        // Test that a replayed keyed transaction is rejected while unkeyed ones are not
+        let mut terminal = setup_terminal();
+        let keyed = |amount| Transaction {
+            idempotency_key: Some(String::from("deposit-0001")),
+            ..Transaction::new(TransactionType::Deposit, "dup_wallet", amount)
+        };
+
+        terminal.record_transaction(keyed(100)).unwrap();
+        assert!(matches!(
+            terminal.record_transaction(keyed(100)),
+            Err(WalletError::DuplicateTransaction(key)) if key == "deposit-0001"
+        ));
+
+        for _ in 0..2 {
+            terminal
+                .record_transaction(Transaction::new(TransactionType::Deposit, "dup_wallet", 100))
+                .unwrap();
+        }
+        let transactions = terminal.transactions().unwrap();
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(calculate_wallet_balance(&transactions, "dup_wallet").unwrap(), 300);
+
+        // Rewinding past a keyed transaction frees its key again
+        let mut rewound = setup_terminal();
+        rewound.checkpoint("empty").unwrap();
+        rewound.record_transaction(keyed(50)).unwrap();
+        assert!(rewound.rewind_to("empty").unwrap());
+        rewound.record_transaction(keyed(50)).unwrap();
+    }
+
+    #[test]
+    fn test_terminal_withdrawal_success_rate() {
+        // Test that rejected withdrawals count as attempts in the success rate
+        let mut terminal = setup_terminal();
+        assert_eq!(terminal.withdrawal_success_rate(), None);
+
+        let script = "deposit rate_wallet 100\nwithdraw rate_wallet 30\nwithdraw rate_wallet 500\n";
+        terminal.run_script(std::io::Cursor::new(script)).unwrap();
+
+        assert_eq!(terminal.withdrawal_success_rate(), Some(0.5));
+
+        // A non-positive amount is a rejected attempt, a malformed script line is not
+        assert!(matches!(terminal.add_withdrawal("rate_wallet", 0), Err(WalletError::InvalidAmount(0))));
+        terminal.run_script(std::io::Cursor::new("withdraw rate_wallet abc\n")).unwrap();
+        assert_eq!(terminal.withdrawal_success_rate(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_terminal_wallet_summary() {
+        // Test that the summary block lists the wallet's figures
+        let mut terminal = setup_terminal();
+        terminal.add_deposit("summary_wallet", 1000).unwrap();
+        terminal.add_withdrawal("summary_wallet", 250).unwrap();
+
+        let mut output = Vec::new();
+        terminal.write_wallet_summary("summary_wallet", &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[..5],
+            [
+                "Summary for wallet summary_wallet",
+                "  Balance:           7.50",
+                "  Transactions:      2",
+                "  Total deposits:    10.00",
+                "  Total withdrawals: 2.50",
+            ]
+        );
+        assert!(lines[5].starts_with("  Largest:           "));
+        assert!(lines[5].ends_with("Deposit of 10.00 to summary_wallet"));
+    }
+
+    #[test]
+    fn test_terminal_undo_last() {
+        // Test that undoing a withdrawal restores the funds it took
+        let mut terminal = setup_terminal();
+        assert_eq!(terminal.undo_last(), None);
+
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Deposit, "undo_wallet", 100))
+            .unwrap();
+        let withdrawal = Transaction {
+            idempotency_key: Some(String::from("undo-withdrawal")),
+            ..Transaction::new(TransactionType::Withdrawal, "undo_wallet", 80)
+        };
+        terminal.record_transaction(withdrawal.clone()).unwrap();
+        assert!(terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "undo_wallet", 50))
+            .is_err());
+
+        assert_eq!(terminal.undo_last(), Some(withdrawal.clone()));
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "undo_wallet").unwrap(), 100);
+        terminal
+            .record_transaction(Transaction::new(TransactionType::Withdrawal, "undo_wallet", 50))
+            .unwrap();
+
+        // The undone transaction's key may be used again
+        terminal.record_transaction(Transaction { amount: Money::from_minor(20), ..withdrawal }).unwrap();
+        assert_eq!(calculate_wallet_balance(&terminal.transactions().unwrap(), "undo_wallet").unwrap(), 30);
+    }
+
+    #[test]
+    fn test_terminal_add_deposit_and_withdrawal() {
+        // Test the prompt-free deposit and withdrawal methods
+        let mut terminal = setup_terminal();
+        assert_eq!(terminal.add_deposit("direct_wallet", 300).unwrap(), 300);
+        assert_eq!(terminal.add_withdrawal("direct_wallet", Money::from_minor(120)).unwrap(), 180);
+
+        assert!(matches!(
+            terminal.add_withdrawal("direct_wallet", 181),
+            Err(WalletError::InsufficientFunds { requested: 181, available: 180 })
+        ));
+        assert!(matches!(terminal.add_deposit("direct_wallet", 0), Err(WalletError::InvalidAmount(0))));
+        assert!(matches!(terminal.add_withdrawal("direct_wallet", -5), Err(WalletError::InvalidAmount(-5))));
+
+        let transactions = terminal.transactions().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].transaction_type, TransactionType::Withdrawal);
+        assert_eq!(calculate_wallet_balance(&transactions, "direct_wallet").unwrap(), 180);
+    }
+
+    #[test]
+    fn test_terminal_with_wallet_index() {
+        // Test that an indexed terminal validates and reports balances like the default one
+        let mut plain = setup_terminal();
+        let mut indexed = WalletTerminal::with_wallet_index();
+
+        for step in 0..600i64 {
+            let wallet = format!("index_term_{}", step % 25);
+            let tx = match step % 4 {
+                3 => Transaction::new(TransactionType::Withdrawal, wallet.as_str(), step % 700),
+                2 => Transaction::new(
+                    TransactionType::Transfer { to: format!("index_term_{}", (step + 7) % 25) },
+                    wallet.as_str(),
+                    step % 300,
+                ),
+                _ => Transaction::new(TransactionType::Deposit, wallet.as_str(), step % 500),
+            };
+            let expected = plain.record_transaction(tx.clone()).map_err(|e| e.kind());
+            assert_eq!(indexed.record_transaction(tx).map_err(|e| e.kind()), expected);
+        }
+
+        let transactions = indexed.transactions().unwrap();
+        assert_eq!(transactions, plain.transactions().unwrap());
+        for n in 0..25 {
+            let wallet = format!("index_term_{}", n);
+            assert_eq!(
+                indexed.wallet_balance(&wallet).unwrap(),
+                calculate_wallet_balance(&transactions, &wallet).unwrap()
+            );
+        }
+
+        // Undo keeps the index in step with the log
+        let removed = indexed.undo_last().unwrap();
+        assert_eq!(
+            indexed.wallet_balance(&removed.wallet_address).unwrap(),
+            calculate_wallet_balance(&indexed.transactions().unwrap(), &removed.wallet_address).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_terminal_rejects_transfer_overflowing_destination() {
+        // Test that a transfer is rejected when crediting the destination would overflow
+        let mut terminal = setup_terminal();
+        terminal.add_deposit("overflow_destination", i64::MAX - 10).unwrap();
+        terminal.add_deposit("overflow_source", 100).unwrap();
+
+        let transfer = |amount| {
+            Transaction::new(
+                TransactionType::Transfer { to: String::from("overflow_destination") },
+                "overflow_source",
+                amount,
+            )
+        };
+        assert!(matches!(
+            terminal.record_transaction(transfer(50)),
+            Err(WalletError::BalanceOverflow { wallet }) if wallet == "overflow_destination"
+        ));
+        assert_eq!(terminal.transactions().unwrap().len(), 2);
+        assert!(validate_history(&terminal.transactions().unwrap()).is_ok());
+
+        terminal.record_transaction(transfer(10)).unwrap();
+        assert_eq!(terminal.wallet_balance("overflow_destination").unwrap(), i64::MAX);
+        assert_eq!(terminal.wallet_balance("overflow_source").unwrap(), 90);
+    }
+
+    #[test]
+    fn test_terminal_script_session_persists_state() {
+        // Test that script sessions continue from the saved log and save their changes
+        initialize("test_terminal");
+        let path = std::env::temp_dir().join(format!("ryz_labs_script_state_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut first = WalletTerminal::with_state_file(&path);
+        first
+            .run_script_session(std::io::Cursor::new("deposit script_state 100\n"))
+            .unwrap();
+
+        let mut second = WalletTerminal::with_state_file(&path);
+        second
+            .run_script_session(std::io::Cursor::new("withdraw script_state 30\n"))
+            .unwrap();
+        assert_eq!(second.wallet_balance("script_state").unwrap(), 7000);
+
+        let saved = WalletTerminal::load_from_file(&path).unwrap();
+        assert_eq!(saved.transactions().unwrap(), second.transactions().unwrap());
+        assert_eq!(saved.transactions().unwrap().len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_terminal_moves_unreadable_state_file_aside() {
+        // Test that a corrupt state file is kept under a new name instead of being overwritten
+        initialize("test_terminal");
+        let path = std::env::temp_dir().join(format!("ryz_labs_corrupt_state_{}.json", std::process::id()));
+        let corrupt = std::env::temp_dir().join(format!("ryz_labs_corrupt_state_{}.json.corrupt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&corrupt);
+        std::fs::write(&path, "not json").unwrap();
+
+        let mut terminal = WalletTerminal::with_state_file(&path);
+        terminal
+            .run_script_session(std::io::Cursor::new("deposit corrupt_state 25\n"))
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&corrupt).unwrap(), "not json");
+        let saved = WalletTerminal::load_from_file(&path).unwrap();
+        assert_eq!(saved.transactions().unwrap().len(), 1);
+        assert_eq!(saved.wallet_balance("corrupt_state").unwrap(), 2500);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&corrupt).unwrap();
+    }
+}